@@ -1,785 +1,8137 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+use base64::Engine;
+use chrono::Timelike;
 use nannou::image;
+use nannou::image::imageops::FilterType;
 use nannou::image::{DynamicImage, GenericImageView, ImageError};
 use nannou::prelude::*;
-use nannou::rand::{thread_rng, Rng};
+use nannou::rand::rngs::StdRng;
+use nannou::rand::seq::SliceRandom;
+use nannou::rand::{Rng, SeedableRng};
 use nannou_egui::{self, egui, Egui};
-use rfd::FileDialog;
+use rfd::{FileDialog, MessageButtons, MessageDialog, MessageLevel};
+use std::cell::RefCell;
+use std::collections::VecDeque;
 use std::env;
-use std::fs::{create_dir_all, File, OpenOptions};
-use std::io::{Read, Write};
+use std::fs::{create_dir_all, OpenOptions};
+use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc;
 use std::sync::Mutex;
 use std::thread;
+use std::time::SystemTime;
 
+#[cfg(windows)]
+use raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
 #[cfg(windows)]
 use winapi::shared::minwindef::FALSE;
 #[cfg(windows)]
 use winapi::shared::windef::HWND;
 #[cfg(windows)]
+use winapi::um::sysinfoapi::GetTickCount;
+#[cfg(windows)]
 use winapi::um::winuser::{
-    FindWindowW, GetClientRect, GetWindowLongPtrW, MoveWindow, SetParent, SetWindowLongPtrW,
-    GWL_STYLE, WS_CHILD, WS_VISIBLE,
+    GetClientRect, GetLastInputInfo, MoveWindow, SetParent, SetWindowLongPtrW, GWL_STYLE,
+    LASTINPUTINFO, WS_CHILD, WS_VISIBLE,
 };
 
 lazy_static::lazy_static! {
     static ref LAST_HUE: Mutex<i32> = Mutex::new(0);
+    /// All randomness goes through this so a configured seed makes a run
+    /// repeatable; see `seed_rng`.
+    static ref RNG: Mutex<StdRng> = Mutex::new(StdRng::from_entropy());
+    static ref ACTIVE_PROFILE: Mutex<Option<String>> = Mutex::new(None);
+    /// `key=value` pairs from `--set`, applied on top of the loaded config.
+    static ref CONFIG_OVERRIDES: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+    /// Where `--stats-json` writes live stats, or `-` for stdout.
+    static ref STATS_JSON_PATH: Mutex<Option<String>> = Mutex::new(None);
+    static ref IMAGE_CACHE: Mutex<ImageCache> = Mutex::new(ImageCache::default());
+    /// Config file given with `--config-file`, taking precedence over
+    /// `CONFIG_PATH_ENV`.
+    static ref CONFIG_FILE: Mutex<Option<PathBuf>> = Mutex::new(None);
+}
+
+/// Writes a line to stderr and to the log file next to the config, since
+/// release builds have no console to print to.
+macro_rules! log_message {
+    ($($arg:tt)*) => {
+        write_log(&format!($($arg)*))
+    };
 }
 
 static PREVIEW_RUNNING: AtomicBool = AtomicBool::new(false);
+static WINDOWED_MODE: AtomicBool = AtomicBool::new(false);
 static mut PREVIEW_PARENT_HWND: Option<isize> = None;
 
 struct ConfigModel {
     egui: Egui,
     config: ScreenSaverConfig,
+    profile: String,
+    profile_names: Vec<String>,
+    new_profile_name: String,
     image_names: Vec<String>,
     custom_image_path: String,
     file_dialog_receiver: Option<mpsc::Receiver<Option<String>>>,
+    file_dialog_target: ImageTarget,
     is_file_dialog_open: bool,
+    validated_path: String,
+    validation_receiver: Option<mpsc::Receiver<Result<DecodedIcon, String>>>,
+    validation_result: Option<Result<(u32, u32), String>>,
+    pending_thumbnail: Option<egui::ColorImage>,
+    thumbnail: Option<egui::TextureHandle>,
+    icon_sizes: Vec<(u32, u32)>,
+    /// The built-in icons, for the background preview.
+    built_in_icons: Vec<egui::TextureHandle>,
+    /// The background image shown in the preview, decoded like the custom icon.
+    previewed_background: String,
+    background_receiver: Option<mpsc::Receiver<Option<egui::ColorImage>>>,
+    background_thumbnail: Option<egui::TextureHandle>,
+    settings_transfer_receiver: Option<mpsc::Receiver<SettingsTransfer>>,
+    window_geometry: WindowGeometry,
+    status_message: Option<String>,
+    stats: Stats,
+    /// The fullscreen screensaver started with "Test Run", while it runs.
+    test_run: Option<std::process::Child>,
     should_exit: bool,
 }
 
+/// Which setting the image picked in the file dialog is for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ImageTarget {
+    Icon,
+    /// Added to the end of the icon set.
+    IconSet,
+    Background,
+}
+
+/// The outcome of an "Export settings" or "Import settings" dialog, which run
+/// on a background thread.
+enum SettingsTransfer {
+    Exported(PathBuf),
+    Imported(Box<ScreenSaverConfig>),
+    Failed(String),
+    Cancelled,
+}
+
+/// The result of decoding the custom icon in the config dialog.
+struct DecodedIcon {
+    dimensions: (u32, u32),
+    thumbnail: egui::ColorImage,
+    /// The sizes an ICO or CUR file contains.
+    icon_sizes: Vec<(u32, u32)>,
+}
+
+/// Corner-hit statistics accumulated across runs of the screensaver.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct Stats {
+    corner_hits: u64,
+    longest_without_corner_secs: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct WindowGeometry {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
 struct Model {
-    image: DynamicImage,
-    original_image: DynamicImage,
-    dvd_rect: Rect,
-    dvd_vel: Vec2,
+    /// The images logos are drawn from, indexed by `Logo::source`. Only the
+    /// first is used unless `varied_logos` is set.
+    images: Vec<LogoImage>,
+    /// The size `images` were scaled to fit.
+    icon_target: (u32, u32),
+    logos: Vec<Logo>,
+    /// How often another logo is added, until there are `max_logos`.
+    spawn_interval: Option<f32>,
+    max_logos: usize,
+    since_spawn: f32,
+    logo_collision: bool,
+    background: Option<Background>,
+    bounce_count: u64,
+    corner_hits: u64,
+    since_corner: f32,
+    longest_without_corner: f32,
+    show_bounce_counter: bool,
+    milestone_effects: bool,
+    milestone_flash: f32,
+    corner_celebration: bool,
+    confetti: Vec<Confetti>,
+    /// Time left showing the corner hit toast.
+    corner_toast: f32,
+    flip_on_bounce: bool,
+    squash_intensity: Option<f32>,
+    /// How many echoes each logo leaves, or 0 for none, and how long they take
+    /// to fade.
+    echo_count: usize,
+    echo_fade: f32,
+    corner_freeze: Option<f32>,
+    stats_json: Option<StatsJson>,
+    recolor_cooldown: f32,
+    attract_mode: bool,
+    since_strobe: f32,
+    night: Option<NightMode>,
+    /// 1.0 by day, or the night brightness while it's night.
+    dim: f32,
+    startup_fade: f32,
+    /// How far, in pixels, and over how many seconds the scene drifts.
+    pixel_shift: Option<(f32, f32)>,
+    watched_folder: Option<WatchedFolder>,
+    image_cycler: Option<ImageCycler>,
+    /// What the shadow and outline are rebuilt from when the logo image changes.
+    image_config: ScreenSaverConfig,
+    image_scale: f32,
+    glow_intensity: Option<f32>,
+    scale_filter: ScaleFilter,
+    wall_margin: f32,
+    /// Where in the window the logos bounce, as fractions of its size.
+    region: Rect,
+    region_border: bool,
+    /// A block logos bounce off, as fractions of the window.
+    obstacle: Option<Rect>,
+    /// The banner's text, background color and edge, if there is one.
+    banner: Option<(String, [u8; 3], BannerPosition)>,
+    /// Where the gravity well is, as fractions of the window, and how strong.
+    gravity_well: Option<(Vec2, f32)>,
+    photo_frame: Option<PhotoFrame>,
+    walls: [WallMode; 4],
+    max_runtime: Option<f32>,
+    easing_strength: Option<f32>,
+    recolor: RecolorSettings,
     m_pos: Option<Vec2>,
+    internal_height: Option<u32>,
+    /// Created by `view` once the window size is known.
+    offscreen: RefCell<Option<Offscreen>>,
+    /// Mouse travel since `mouse_travel_since`, checked against
+    /// `mouse_exit_distance`.
+    mouse_travel: f32,
+    mouse_travel_since: f32,
+    mouse_exit_distance: f32,
+    exit_only_on_escape: bool,
+    exit_animation: bool,
+    /// Time since exiting was requested, while the exit animation plays.
+    exiting: Option<f32>,
     is_preview: bool,
+    windowed: bool,
+    paused: bool,
+    occluded: bool,
+    minimized: bool,
     #[allow(dead_code)]
     preview_parent: Option<isize>,
+    #[allow(dead_code)]
+    preview_child: Option<isize>,
 }
 
-#[derive(Clone)]
-struct ScreenSaverConfig {
-    speed: f32,
-    image_index: usize,
-    size_factor: f32,
-    custom_image_path: String,
+/// An icon as `get_image_data` takes it.
+#[derive(Clone, Debug, PartialEq)]
+enum IconSource {
+    /// One of the icons compiled in, by index.
+    BuiltIn(usize),
+    /// An image file.
+    Custom(PathBuf),
 }
 
-fn main() {
-    let args: Vec<String> = env::args().collect();
-
-    if args.len() == 1 {
-        nannou::app(model)
-            .update(update)
-            .loop_mode(nannou::LoopMode::Rate { 
-                update_interval: std::time::Duration::from_secs_f64(1.0 / 60.0) 
-            })
-            .run();
-        return;
+impl Default for IconSource {
+    fn default() -> Self {
+        IconSource::BuiltIn(0)
     }
+}
 
-    let flag = &args[1].to_lowercase();
-
-    if flag.starts_with("/c") || flag.starts_with("-c") {
-        show_configuration_dialog();
-    } else if flag.starts_with("/p") || flag.starts_with("-p") {
-        let hwnd = parse_preview_hwnd(&args);
-        run_preview_mode(hwnd);
-    } else if flag.starts_with("/s") || flag.starts_with("-s") {
-        nannou::app(model).update(update).run();
-    } else if flag.starts_with("/a") || flag.starts_with("-a") {
-        std::process::exit(0);
-    } else {
-        nannou::app(model).update(update).run();
+impl IconSource {
+    fn label(&self) -> String {
+        match self {
+            IconSource::BuiltIn(index) => BUILT_IN_ICON_NAMES
+                .get(*index)
+                .unwrap_or(&BUILT_IN_ICON_NAMES[0])
+                .to_string(),
+            IconSource::Custom(path) if is_data_uri(&path.to_string_lossy()) => {
+                "Embedded image".to_string()
+            }
+            IconSource::Custom(path) => path
+                .file_name()
+                .unwrap_or(path.as_os_str())
+                .to_string_lossy()
+                .to_string(),
+        }
     }
 }
 
-fn parse_preview_hwnd(args: &[String]) -> Option<isize> {
-    if args.len() > 2 {
-        args[2].parse::<isize>().ok()
-    } else if args.len() > 1 {
-        let flag = &args[1];
-        if flag.len() > 2 {
-            flag[2..].parse::<isize>().ok()
-        } else {
-            None
+impl std::fmt::Display for IconSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            IconSource::BuiltIn(index) => write!(f, "builtin:{}", index),
+            IconSource::Custom(path) => write!(f, "custom:{}", path.display()),
         }
-    } else {
-        None
     }
 }
 
-fn run_preview_mode(hwnd: Option<isize>) {
-    if PREVIEW_RUNNING.swap(true, Ordering::SeqCst) {
-        std::process::exit(0);
-    }
+impl std::str::FromStr for IconSource {
+    type Err = String;
 
-    unsafe {
-        PREVIEW_PARENT_HWND = hwnd;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some(("builtin", index)) => index
+                .parse()
+                .map(IconSource::BuiltIn)
+                .map_err(|_| format!("Unknown built-in icon '{}'", index)),
+            Some(("custom", path)) if !path.is_empty() => Ok(IconSource::Custom(path.into())),
+            _ => Err(format!("Unknown icon '{}'", s)),
+        }
     }
+}
 
-    if hwnd.is_some() {
-        nannou::app(preview_model_embedded).update(update).run();
-    } else {
-        nannou::app(preview_model_standalone).update(update).run();
-    }
+/// A logo image and the effects built from its silhouette.
+struct LogoImage {
+    /// Where `original` was loaded from, to reload it at a new size.
+    icon: IconSource,
+    original: DynamicImage,
+    /// The part of the image logos collide with, relative to its center.
+    visible: Rect,
+    shadow: Option<Shadow>,
+    outline: Option<Outline>,
+    neon_glow: Option<NeonGlow>,
+    /// `original` recolored at evenly spaced hues, for `ColorMode::RainbowCycle`.
+    rainbow: Vec<RainbowFrame>,
+}
 
-    PREVIEW_RUNNING.store(false, Ordering::SeqCst);
+struct RainbowFrame {
+    image: DynamicImage,
+    premultiplied: DynamicImage,
+    color: Srgb<u8>,
 }
 
-#[cfg(windows)]
-fn preview_model_embedded(app: &App) -> Model {
-    let parent_hwnd = unsafe { PREVIEW_PARENT_HWND };
+/// How many hues `ColorMode::RainbowCycle` caches per image; recoloring every
+/// frame would be far too slow.
+const RAINBOW_FRAMES: usize = 36;
 
-    let _window_id = app
-        .new_window()
-        .size(200, 150)
-        .title("DVD Screensaver Preview")
-        .event(window_event)
-        .view(view)
-        .decorations(false)
-        .resizable(false)
-        .build()
-        .unwrap();
+struct Background {
+    texture: wgpu::Texture,
+    fit: BackgroundFit,
+}
 
-    if let Some(parent_hwnd) = parent_hwnd {
-        thread::spawn(move || {
-            thread::sleep(std::time::Duration::from_millis(200));
+/// A folder whose newest image is shown as the logo, swapped in when a newer
+/// one appears.
+struct WatchedFolder {
+    folder: PathBuf,
+    current: Option<(PathBuf, SystemTime)>,
+    since_poll: f32,
+    target_size: (u32, u32),
+}
+
+/// The images of a folder or the icon set, loaded up front, that the logo
+/// steps through.
+struct ImageCycler {
+    images: Vec<(IconSource, DynamicImage)>,
+    next: usize,
+    mode: ImageCycle,
+    interval: f32,
+    since_swap: f32,
+}
 
-            unsafe {
-                let window_title = std::ffi::CString::new("DVD Screensaver Preview").unwrap();
-                let mut title_wide: Vec<u16> =
-                    window_title.to_string_lossy().encode_utf16().collect();
-                title_wide.push(0);
+/// A blurred copy of the logo's silhouette, drawn behind it as a glow.
+struct NeonGlow {
+    mask: image::GrayImage,
+    /// How far the blur extends past each edge of the logo.
+    padding: f32,
+    /// The glow in the configured color, unless it follows the logo's color.
+    fixed: Option<DynamicImage>,
+}
+
+/// The logo's silhouette in the outline color, drawn offset around the logo.
+struct Outline {
+    image: DynamicImage,
+    thickness: f32,
+}
 
-                let child_hwnd = FindWindowW(std::ptr::null(), title_wide.as_ptr());
+struct Shadow {
+    image: DynamicImage,
+    offset: Vec2,
+}
 
-                if !child_hwnd.is_null() {
-                    let parent = parent_hwnd as HWND;
+struct Logo {
+    /// Which of the model's images this logo shows.
+    source: usize,
+    image: DynamicImage,
+    /// `image` with premultiplied alpha, as it's drawn.
+    premultiplied: DynamicImage,
+    /// What the logo collides with: the image's `visible` part.
+    rect: Rect,
+    visible: Rect,
+    vel: Vec2,
+    color: Srgb<u8>,
+    glow_timer: f32,
+    bounce_tracker: BounceTracker,
+    /// The neon glow in this logo's color, if it follows the logo's color.
+    neon_image: Option<DynamicImage>,
+    /// Time left in the squash after hitting a side wall (x) or the top or
+    /// bottom (y), and which side was hit.
+    squash_timer: Vec2,
+    squash_side: Vec2,
+    echoes: VecDeque<Echo>,
+    /// Time left holding still after a corner hit.
+    freeze_timer: f32,
+    since_recolor: f32,
+    /// The logo's hue in degrees, advanced every frame in rainbow mode.
+    hue: f32,
+    /// Which of the image's rainbow frames is shown, if any yet.
+    rainbow_frame: Option<usize>,
+    /// Time left in the brightness flash after a bounce, for
+    /// `ColorMode::BrightnessPulse`.
+    pulse_timer: f32,
+    /// `premultiplied` brightened in steps, drawn during the flash.
+    pulse_frames: Vec<DynamicImage>,
+}
 
-                    let mut client_rect = std::mem::zeroed();
-                    if GetClientRect(parent, &mut client_rect) != FALSE {
-                        let width = client_rect.right - client_rect.left;
-                        let height = client_rect.bottom - client_rect.top;
+/// A still copy of a logo left where it bounced, fading out.
+struct Echo {
+    xy: Vec2,
+    wh: Vec2,
+    image: DynamicImage,
+    /// `image` at the opacity of the current fade step, premultiplied.
+    faded: DynamicImage,
+    step: u32,
+    age: f32,
+}
 
-                        let _current_style = GetWindowLongPtrW(child_hwnd, GWL_STYLE);
+/// Echoes are re-faded in steps, as the alpha has to be applied per pixel.
+const ECHO_FADE_STEPS: u32 = 8;
 
-                        SetParent(child_hwnd, parent);
+/// Opacity of a fresh echo.
+const ECHO_OPACITY: f32 = 0.5;
 
-                        let new_style = (WS_CHILD | WS_VISIBLE) as isize;
-                        SetWindowLongPtrW(child_hwnd, GWL_STYLE, new_style);
+impl Echo {
+    fn new(logo: &Logo, flip: bool) -> Self {
+        let (xy, wh) = logo.draw_area(flip, None);
+        Echo {
+            xy,
+            wh,
+            image: logo.image.clone(),
+            faded: premultiply_alpha(&with_opacity(&logo.image, ECHO_OPACITY)),
+            step: ECHO_FADE_STEPS,
+            age: 0.0,
+        }
+    }
 
-                        MoveWindow(child_hwnd, 0, 0, width, height, 1);
-                    }
-                }
-            }
-        });
+    /// Ages the echo, returning false once it has faded out.
+    fn fade(&mut self, delta_time: f32, duration: f32) -> bool {
+        self.age += delta_time;
+        let remaining = 1.0 - self.age / duration;
+        if remaining <= 0.0 {
+            return false;
+        }
+
+        let step = (remaining * ECHO_FADE_STEPS as f32).ceil() as u32;
+        if step != self.step {
+            self.step = step;
+            let opacity = ECHO_OPACITY * step as f32 / ECHO_FADE_STEPS as f32;
+            self.faded = premultiply_alpha(&with_opacity(&self.image, opacity));
+        }
+        true
     }
+}
 
-    create_preview_model(true, parent_hwnd)
+fn with_opacity(image: &DynamicImage, opacity: f32) -> DynamicImage {
+    let mut image = image.to_rgba8();
+    for pixel in image.pixels_mut() {
+        pixel[3] = (pixel[3] as f32 * opacity) as u8;
+    }
+    DynamicImage::ImageRgba8(image)
 }
 
-#[cfg(not(windows))]
-fn preview_model_embedded(app: &App) -> Model {
-    preview_model_standalone(app)
+/// Adjustments applied to a logo's pixels each time it is recolored.
+#[derive(Clone, Copy, Debug)]
+struct RecolorSettings {
+    color_mode: ColorMode,
+    brightness: i32,
+    contrast: f32,
+    saturation: f32,
+    invert: bool,
+    opacity: f32,
 }
 
-fn preview_model_standalone(app: &App) -> Model {
-    let _window_id = app
-        .new_window()
-        .size(200, 150)
-        .title("DVD Screensaver Preview")
-        .event(window_event)
-        .view(view)
-        .decorations(true)
-        .always_on_top(true)
-        .resizable(false)
-        .msaa_samples(4)
-        .build()
-        .unwrap();
+impl RecolorSettings {
+    fn from_config(config: &ScreenSaverConfig) -> Self {
+        RecolorSettings {
+            color_mode: config.color_mode,
+            brightness: config.brightness,
+            contrast: config.contrast,
+            saturation: config.saturation.max(0.0),
+            invert: config.invert,
+            opacity: config.logo_opacity.clamp(0.0, 1.0),
+        }
+    }
+}
+
+/// Every this many wall bounces, the screen flashes if milestone effects are
+/// enabled.
+const BOUNCE_MILESTONE: u64 = 100;
 
-    create_preview_model(true, None)
+/// How long, in seconds, the milestone flash takes to fade.
+const MILESTONE_FLASH_DURATION: f32 = 0.6;
+
+/// A scrap of confetti from a corner hit celebration.
+struct Confetti {
+    xy: Vec2,
+    vel: Vec2,
+    color: [f32; 3],
+    life: f32,
 }
 
-fn create_preview_model(is_preview: bool, parent_hwnd: Option<isize>) -> Model {
-    let config = load_config();
+const CONFETTI_COUNT: usize = 60;
+const CONFETTI_LIFETIME: f32 = 1.5;
+const CORNER_TOAST_DURATION: f32 = 2.0;
 
-    let preview_size = if parent_hwnd.is_some() {
-        (100.0, 75.0)
-    } else {
-        (200.0, 150.0)
-    };
+/// A burst of confetti from `origin`, with speeds relative to the window height
+/// so it looks the same in the preview.
+fn spawn_confetti(origin: Vec2, window_height: f32) -> Vec<Confetti> {
+    let mut rng = RNG.lock().unwrap();
+    (0..CONFETTI_COUNT)
+        .map(|_| {
+            let angle = rng.gen_range(0.0..TAU);
+            let speed = window_height * rng.gen_range(0.2..0.7);
+            Confetti {
+                xy: origin,
+                vel: Vec2::new(angle.cos(), angle.sin()) * speed,
+                color: hsv_to_rgb(rng.gen_range(0.0..360.0), 0.8, 1.0),
+                life: CONFETTI_LIFETIME * rng.gen_range(0.6..=1.0),
+            }
+        })
+        .collect()
+}
+
+/// Shows the image filling the screen, slowly panning and zooming between
+/// random shots instead of bouncing (the Ken Burns effect).
+struct PhotoFrame {
+    pan_secs: f32,
+    zoom: (f32, f32),
+    /// Shows the logo's color instead of the image's own, changing with each pan.
+    recolor: bool,
+    /// How far into the current pan, in seconds.
+    elapsed: f32,
+    from: PhotoShot,
+    to: PhotoShot,
+    /// The image as uploaded, dropped whenever the image changes.
+    texture: Option<wgpu::Texture>,
+}
+
+/// How far a photo frame is zoomed in, and where it's looking: from -1 to 1
+/// of how far it can pan at that zoom on each axis.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct PhotoShot {
+    zoom: f32,
+    center: Vec2,
+}
 
-    let original_image = match get_image_data(config.image_index, &config.custom_image_path) {
-        Ok(img) => {
-            let target_width = (preview_size.0 * config.size_factor * 2.0) as u32;
-            let target_height = (preview_size.1 * config.size_factor * 2.0) as u32;
+impl PhotoShot {
+    fn random(zoom: (f32, f32)) -> Self {
+        let mut rng = RNG.lock().unwrap();
+        PhotoShot {
+            zoom: if zoom.0 < zoom.1 {
+                rng.gen_range(zoom.0..=zoom.1)
+            } else {
+                zoom.0
+            },
+            center: Vec2::new(rng.gen_range(-1.0..=1.0), rng.gen_range(-1.0..=1.0)),
+        }
+    }
+}
 
-            img.thumbnail(target_width.max(40), target_height.max(30))
+impl PhotoFrame {
+    fn new(config: &ScreenSaverConfig) -> Option<Self> {
+        if !config.photo_frame {
+            return None;
         }
-        Err(_) => {
-            let data = include_bytes!("../assets/dvd_logo.png");
-            let default_img = image::load_from_memory(data).expect("Unable to load default icon");
 
-            let target_width = (preview_size.0 * config.size_factor * 2.0) as u32;
-            let target_height = (preview_size.1 * config.size_factor * 2.0) as u32;
+        let min = config.photo_zoom_min.clamp(1.0, 3.0);
+        let zoom = (min, config.photo_zoom_max.clamp(min, 3.0));
+        Some(PhotoFrame {
+            pan_secs: config.photo_pan_secs.clamp(5.0, 120.0),
+            zoom,
+            recolor: config.photo_recolor,
+            elapsed: 0.0,
+            from: PhotoShot::random(zoom),
+            to: PhotoShot::random(zoom),
+            texture: None,
+        })
+    }
 
-            default_img.thumbnail(target_width.max(40), target_height.max(30))
+    /// Moves the pan along, returning whether it reached its shot and set off
+    /// towards a new one.
+    fn advance(&mut self, delta_time: f32) -> bool {
+        self.elapsed += delta_time;
+        if self.elapsed < self.pan_secs {
+            return false;
         }
-    };
 
-    let image = change_color(&original_image);
+        self.elapsed = 0.0;
+        self.from = self.to;
+        self.to = PhotoShot::random(self.zoom);
+        true
+    }
+
+    /// The part of the image to show right now, in texture coordinates.
+    fn source_area(&self) -> Rect {
+        let t = (self.elapsed / self.pan_secs).clamp(0.0, 1.0);
+        let t = t * t * (3.0 - 2.0 * t);
+        let zoom = self.from.zoom + (self.to.zoom - self.from.zoom) * t;
+        let center = self.from.center.lerp(self.to.center, t);
 
-    let rect = Rect::from_x_y_w_h(
-        0.0,
-        0.0,
-        image.dimensions().0 as f32,
-        image.dimensions().1 as f32,
-    );
+        let size = 1.0 / zoom.max(1.0);
+        let reach = (1.0 - size) / 2.0;
+        Rect::from_x_y_w_h(0.5 + center.x * reach, 0.5 + center.y * reach, size, size)
+    }
+}
+
+/// How long, in seconds, the attract mode strobe lasts after starting, and how
+/// often it changes color meanwhile.
+const ATTRACT_DURATION: f32 = 2.0;
+const ATTRACT_STROBE_INTERVAL: f32 = 0.1;
+
+/// How long, in seconds, the halo drawn after a color change takes to fade.
+const GLOW_DURATION: f32 = 0.4;
+
+/// Dims the screen at night, by the local clock.
+#[derive(Clone, Copy, Debug)]
+struct NightMode {
+    brightness: f32,
+    start_hour: u32,
+    end_hour: u32,
+}
 
-    Model {
-        image,
-        original_image,
-        dvd_rect: rect,
-        dvd_vel: Vec2::new(config.speed * 0.5, config.speed * 0.5),
-        m_pos: None,
-        is_preview,
-        preview_parent: parent_hwnd,
+impl NightMode {
+    fn from_config(config: &ScreenSaverConfig) -> Option<Self> {
+        config.night_mode.then_some(NightMode {
+            brightness: config.night_brightness.clamp(0.0, 1.0),
+            start_hour: config.night_start_hour % 24,
+            end_hour: config.night_end_hour % 24,
+        })
+    }
+
+    /// Whether `hour` falls in the night, which may span midnight.
+    fn is_night(&self, hour: u32) -> bool {
+        if self.start_hour <= self.end_hour {
+            (self.start_hour..self.end_hour).contains(&hour)
+        } else {
+            hour >= self.start_hour || hour < self.end_hour
+        }
+    }
+
+    fn dim(&self) -> f32 {
+        if self.is_night(chrono::Local::now().hour()) {
+            self.brightness
+        } else {
+            1.0
+        }
     }
 }
 
-fn show_configuration_dialog() {
-    nannou::app(config_model)
-        .update(config_update)
-        .view(config_view)
-        .run();
+/// How often, in seconds, `--stats-json` output is refreshed.
+const STATS_JSON_INTERVAL: f32 = 5.0;
+
+/// Periodic JSON stats for external monitoring, enabled with `--stats-json`.
+struct StatsJson {
+    target: String,
+    since_write: f32,
+    /// The active config as a JSON object, which doesn't change during a run.
+    config: String,
 }
 
-fn config_model(app: &App) -> ConfigModel {
-    let _window_id = app
-        .new_window()
-        .size(500, 400)
-        .title("DVD Screensaver Configuration")
-        .view(config_view)
-        .raw_event(raw_window_event)
-        .build()
-        .unwrap();
+impl StatsJson {
+    fn from_args(config: &ScreenSaverConfig) -> Option<Self> {
+        let target = STATS_JSON_PATH.lock().unwrap().clone()?;
 
-    let window = app.window(_window_id).unwrap();
-    let egui = Egui::from_window(&window);
+        let mut dump = Vec::new();
+        let _ = write_config_dump(&mut dump, config);
+        let entries: Vec<String> = String::from_utf8_lossy(&dump)
+            .lines()
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| format!("{}:{}", json_string(key), json_config_value(key, value)))
+            .collect();
 
-    let config = load_config();
-    let image_names = vec![
-        "Built-in DVD Logo".to_string(),
-        "Built-in DVD Logo 2".to_string(),
-        "Custom Icon".to_string(),
-    ];
+        Some(StatsJson {
+            target,
+            // Write the first report right away.
+            since_write: STATS_JSON_INTERVAL,
+            config: format!("{{{}}}", entries.join(",")),
+        })
+    }
 
-    ConfigModel {
-        egui,
-        config: config.clone(),
-        image_names,
-        custom_image_path: config.custom_image_path,
-        file_dialog_receiver: None,
-        is_file_dialog_open: false,
-        should_exit: false,
+    fn write(&self, app: &App, model: &Model) {
+        let json = format!(
+            "{{\"uptime_secs\":{:.1},\"bounce_count\":{},\"corner_hits\":{},\"fps\":{:.1},\"config\":{}}}",
+            app.time,
+            model.bounce_count,
+            model.corner_hits,
+            app.fps(),
+            self.config
+        );
+
+        if self.target == "-" {
+            println!("{}", json);
+            return;
+        }
+
+        // Replaced in one step so readers never see a half-written file.
+        let temp_path = format!("{}.tmp", self.target);
+        let result = std::fs::write(&temp_path, json + "\n")
+            .and_then(|()| std::fs::rename(&temp_path, &self.target));
+        if let Err(error) = result {
+            log_message!("Unable to write stats to {}: {}", self.target, error);
+        }
     }
 }
 
-fn config_update(_app: &App, model: &mut ConfigModel, update: Update) {
-    if model.should_exit {
-        std::process::exit(0);
+/// A value from the config dump as JSON: numbers and booleans as they are,
+/// unset optional settings as null, and the rest quoted. Colors, paths and
+/// text stay strings even when they happen to look like numbers.
+fn json_config_value(key: &str, value: &str) -> String {
+    let is_text = key.ends_with("_color")
+        || key.ends_with("_path")
+        || matches!(key, "banner_text" | "icon_set");
+    if is_text {
+        return json_string(value);
     }
 
-    let egui = &mut model.egui;
-    egui.set_elapsed_time(update.since_start);
+    match value {
+        "" => "null".to_string(),
+        "true" | "false" => value.to_string(),
+        _ => match value.parse::<f64>() {
+            Ok(number) if number.is_finite() => number.to_string(),
+            _ => json_string(value),
+        },
+    }
+}
 
-    if let Some(receiver) = &model.file_dialog_receiver {
-        if let Ok(result) = receiver.try_recv() {
-            model.is_file_dialog_open = false;
-            if let Some(path) = result {
-                model.custom_image_path = path;
-                model.config.custom_image_path = model.custom_image_path.clone();
-            }
-            model.file_dialog_receiver = None;
+fn json_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
         }
     }
+    escaped.push('"');
+    escaped
+}
 
-    let ctx = egui.begin_frame();
-
-    let mut fonts = egui::FontDefinitions::default();
+/// How long, in seconds, a logo takes to spring back after squashing.
+const SQUASH_DURATION: f32 = 0.3;
 
-    if let Ok(font_data) = std::fs::read("C:/Windows/Fonts/segoeui.ttf") {
-        fonts
-            .font_data
-            .insert("Segoe UI".to_owned(), egui::FontData::from_owned(font_data));
+/// How long, in seconds, the brightness flash takes to fade, and in how many
+/// cached steps.
+const PULSE_DURATION: f32 = 0.25;
+const PULSE_STEPS: usize = 5;
 
-        fonts
-            .families
-            .entry(egui::FontFamily::Proportional)
-            .or_default()
-            .insert(0, "Segoe UI".to_owned());
+impl Logo {
+    /// Where and at what size to draw the logo. A negative width mirrors the
+    /// texture, so `flip` turns the logo around while it is moving left.
+    /// Squashing only changes the drawn size, never `rect`.
+    fn draw_area(&self, flip: bool, squash_intensity: Option<f32>) -> (Vec2, Vec2) {
+        let flipped = flip && self.vel.x < 0.0;
+        let mut offset = self.visible.xy();
+        if flipped {
+            offset.x = -offset.x;
+        }
+        let (width, height) = self.image.dimensions();
+        let mut xy = self.rect.xy() - offset;
+        let mut wh = Vec2::new(width as f32, height as f32);
 
-        fonts
-            .families
-            .entry(egui::FontFamily::Monospace)
-            .or_default()
-            .push("Segoe UI".to_owned());
+        if let Some(intensity) = squash_intensity {
+            // Squashes flat, overshoots into a stretch and then settles.
+            let t = self.squash_timer / SQUASH_DURATION;
+            let amount = Vec2::new(
+                intensity * t.x * ((1.0 - t.x) * TAU).cos(),
+                intensity * t.y * ((1.0 - t.y) * TAU).cos(),
+            );
+            let scale = Vec2::new(
+                (1.0 - amount.x) * (1.0 + amount.y),
+                (1.0 - amount.y) * (1.0 + amount.x),
+            );
+            // Stay pressed against the wall that was hit.
+            xy += self.squash_side * wh * (Vec2::ONE - scale) / 2.0;
+            wh *= scale;
+        }
 
-        ctx.set_fonts(fonts);
+        if flipped {
+            wh.x = -wh.x;
+        }
+        (xy, wh)
     }
 
-    let mut window_open = true;
-    egui::Window::new("DVD Screensaver Settings")
-        .default_size([450.0, 350.0])
-        .open(&mut window_open)
-        .show(&ctx, |ui| {
-            ui.heading("Movement Speed");
-            ui.add(egui::Slider::new(&mut model.config.speed, 10.0..=200.0).text("pixels/second"));
+    fn squash(&mut self, bounces: BounceFlags) {
+        if bounces.left || bounces.right {
+            self.squash_timer.x = SQUASH_DURATION;
+            self.squash_side.x = if bounces.left { -1.0 } else { 1.0 };
+        }
+        if bounces.bottom || bounces.top {
+            self.squash_timer.y = SQUASH_DURATION;
+            self.squash_side.y = if bounces.bottom { -1.0 } else { 1.0 };
+        }
+    }
 
-            ui.separator();
+    /// Recolors the logo from `images[self.source]`.
+    fn recolor(&mut self, images: &[LogoImage], settings: &RecolorSettings) {
+        let source = &images[self.source];
+        self.image = change_color(&source.original, &source.icon, settings);
+        self.premultiplied = premultiply_alpha(&self.image);
+        self.color = average_color(&self.image);
+        self.glow_timer = GLOW_DURATION;
+        self.since_recolor = 0.0;
+        self.neon_image = neon_image_for(source.neon_glow.as_ref(), self.color);
+        self.hue = *LAST_HUE.lock().unwrap() as f32;
+        self.rainbow_frame = None;
+        self.show_rainbow_frame(images);
+        self.pulse_frames = pulse_frames(&self.image, settings);
+    }
 
-            ui.heading("Icon Selection");
-            egui::ComboBox::from_label("Select Icon")
-                .selected_text(
-                    &model.image_names[model.config.image_index.min(model.image_names.len() - 1)],
-                )
-                .show_ui(ui, |ui| {
-                    for (i, name) in model.image_names.iter().enumerate() {
-                        ui.selectable_value(&mut model.config.image_index, i, name);
-                    }
-                });
+    /// Flashes the logo brighter instead of recoloring it.
+    fn pulse(&mut self) {
+        self.pulse_timer = PULSE_DURATION;
+        self.glow_timer = GLOW_DURATION;
+        self.since_recolor = 0.0;
+    }
 
-            if model.config.image_index == 2 {
-                ui.label("Custom icon path:");
-                ui.text_edit_singleline(&mut model.custom_image_path);
+    /// The premultiplied image to draw, brightened while it flashes.
+    fn drawn_image(&self) -> &DynamicImage {
+        if self.pulse_timer <= 0.0 || self.pulse_frames.is_empty() {
+            return &self.premultiplied;
+        }
 
-                ui.horizontal(|ui| {
-                    let button_text = if model.is_file_dialog_open {
-                        "File dialog is open..."
-                    } else {
-                        "Browse File"
-                    };
+        let steps = self.pulse_frames.len();
+        let step = (self.pulse_timer / PULSE_DURATION * steps as f32).ceil() as usize;
+        &self.pulse_frames[step.clamp(1, steps) - 1]
+    }
 
-                    if ui
-                        .add_enabled(!model.is_file_dialog_open, egui::Button::new(button_text))
-                        .clicked()
-                    {
-                        let (sender, receiver) = mpsc::channel();
-                        model.file_dialog_receiver = Some(receiver);
-                        model.is_file_dialog_open = true;
+    /// Swaps in the cached rainbow frame closest to `hue`, if the image has any.
+    fn show_rainbow_frame(&mut self, images: &[LogoImage]) {
+        let source = &images[self.source];
+        let frames = &source.rainbow;
+        if frames.is_empty() {
+            return;
+        }
 
-                        thread::spawn(move || {
-                            let result = FileDialog::new()
-                                .add_filter(
-                                    "Image Files",
-                                    &[
-                                        "png", "jpg", "jpeg", "gif", "bmp", "ico", "tiff", "tif",
-                                        "webp",
-                                    ],
-                                )
-                                .add_filter("PNG Files", &["png"])
-                                .add_filter("JPEG Files", &["jpg", "jpeg"])
-                                .add_filter("GIF Files", &["gif"])
-                                .add_filter("BMP Files", &["bmp"])
-                                .add_filter("ICO Files", &["ico"])
-                                .add_filter("TIFF Files", &["tiff", "tif"])
-                                .add_filter("WebP Files", &["webp"])
-                                .add_filter("All Files", &["*"])
-                                .set_title("Select Icon File")
-                                .pick_file();
-
-                            let path_string = result.map(|path| path.to_string_lossy().to_string());
-                            let _ = sender.send(path_string);
-                        });
-                    }
+        let index = (self.hue / 360.0 * frames.len() as f32).round() as usize % frames.len();
+        if self.rainbow_frame == Some(index) {
+            return;
+        }
 
-                    ui.label("Supported formats: PNG, JPG, GIF, BMP, ICO, TIFF, WebP");
-                });
+        let frame = &frames[index];
+        self.image = frame.image.clone();
+        self.premultiplied = frame.premultiplied.clone();
+        self.color = frame.color;
+        self.neon_image = neon_image_for(source.neon_glow.as_ref(), self.color);
+        self.rainbow_frame = Some(index);
+    }
+}
 
-                if !model.custom_image_path.is_empty() {
-                    let path = Path::new(&model.custom_image_path);
-                    if path.exists() {
-                        if let Some(extension) = path.extension() {
-                            let ext = extension.to_string_lossy().to_lowercase();
-                            let supported_formats = [
-                                "png", "jpg", "jpeg", "gif", "bmp", "ico", "tiff", "tif", "webp",
-                            ];
-                            if supported_formats.contains(&ext.as_str()) {
-                                ui.colored_label(
-                                    egui::Color32::GREEN,
-                                    "✓ File exists and format is supported",
-                                );
-                            } else {
-                                ui.colored_label(
-                                    egui::Color32::from_rgb(255, 165, 0),
-                                    "⚠ File exists but format may not be supported",
-                                );
-                            }
-                        } else {
-                            ui.colored_label(
-                                egui::Color32::from_rgb(255, 165, 0),
-                                "⚠ File exists but has no extension",
-                            );
-                        }
-                    } else {
-                        ui.colored_label(egui::Color32::RED, "✗ File does not exist");
-                    }
-                }
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ScaleFilter {
+    Smooth,
+    Nearest,
+}
 
-                model.config.custom_image_path = model.custom_image_path.clone();
+impl ScaleFilter {
+    const ALL: [ScaleFilter; 2] = [ScaleFilter::Smooth, ScaleFilter::Nearest];
 
-                ui.separator();
-                ui.label("Quick select:");
-                ui.horizontal(|ui| {
-                    if ui.small_button("Desktop").clicked() {
-                        if let Some(desktop) = dirs::desktop_dir() {
-                            model.custom_image_path = desktop.to_string_lossy().to_string();
-                        }
-                    }
-                    if ui.small_button("Pictures").clicked() {
-                        if let Some(pictures) = dirs::picture_dir() {
-                            model.custom_image_path = pictures.to_string_lossy().to_string();
-                        }
-                    }
-                    if ui.small_button("Downloads").clicked() {
-                        if let Some(downloads) = dirs::download_dir() {
-                            model.custom_image_path = downloads.to_string_lossy().to_string();
-                        }
-                    }
-                });
-            }
+    fn label(&self) -> &'static str {
+        match self {
+            ScaleFilter::Smooth => "Smooth",
+            ScaleFilter::Nearest => "Nearest neighbor (pixel art)",
+        }
+    }
+}
 
-            ui.separator();
+impl std::fmt::Display for ScaleFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ScaleFilter::Smooth => write!(f, "smooth"),
+            ScaleFilter::Nearest => write!(f, "nearest"),
+        }
+    }
+}
 
-            ui.heading("Icon Size");
-            ui.add(
-                egui::Slider::new(&mut model.config.size_factor, 0.05..=0.5)
-                    .text("size multiplier"),
-            );
+impl std::str::FromStr for ScaleFilter {
+    type Err = String;
 
-            ui.separator();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "smooth" => Ok(ScaleFilter::Smooth),
+            "nearest" => Ok(ScaleFilter::Nearest),
+            _ => Err(format!("Unknown scale filter '{}'", s)),
+        }
+    }
+}
 
-            ui.horizontal(|ui| {
-                if ui.button("Save and Exit").clicked() {
-                    save_config(&model.config);
-                    model.should_exit = true;
-                }
+/// How the logo's size is chosen: as a fraction of the screen (using
+/// `size_factor`), or as a fixed length in pixels for its longest edge.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SizeMode {
+    Fraction,
+    Pixels(u32),
+}
 
-                if ui.button("Cancel").clicked() {
-                    model.should_exit = true;
-                }
+impl SizeMode {
+    const DEFAULT_PIXELS: u32 = 200;
+}
 
-                if ui.button("Reset to Default").clicked() {
-                    model.config = ScreenSaverConfig {
-                        speed: 50.0,
-                        image_index: 0,
-                        size_factor: 0.16,
-                        custom_image_path: String::new(),
-                    };
-                    model.custom_image_path = String::new();
-                }
-            });
+impl std::fmt::Display for SizeMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SizeMode::Fraction => write!(f, "fraction"),
+            SizeMode::Pixels(pixels) => write!(f, "pixels:{}", pixels),
+        }
+    }
+}
 
-            ui.separator();
-            ui.small("Tip: Drag image files to the path field to quickly set the path");
-        });
+impl std::str::FromStr for SizeMode {
+    type Err = String;
 
-    if !window_open {
-        model.should_exit = true;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            None if s == "fraction" => Ok(SizeMode::Fraction),
+            Some(("pixels", pixels)) => pixels
+                .parse()
+                .map(SizeMode::Pixels)
+                .map_err(|_| format!("Invalid pixel size '{}'", pixels)),
+            _ => Err(format!("Unknown size mode '{}'", s)),
+        }
     }
 }
 
-fn config_view(_app: &App, model: &ConfigModel, frame: Frame) {
-    frame.clear(nannou::color::rgb(0.1, 0.1, 0.1));
-    model.egui.draw_to_frame(&frame).unwrap();
+/// What happens when a logo reaches one of the window's walls.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum WallMode {
+    /// Bounce back, as a classic DVD logo does.
+    Reflect,
+    /// Pass through and come back in from the opposite wall.
+    Wrap,
+    /// Leave the screen and respawn in the middle.
+    Open,
 }
 
-fn raw_window_event(
-    _app: &App,
-    model: &mut ConfigModel,
-    event: &nannou::winit::event::WindowEvent,
-) {
-    model.egui.handle_raw_event(event);
+impl WallMode {
+    const ALL: [WallMode; 3] = [WallMode::Reflect, WallMode::Wrap, WallMode::Open];
 
-    if let nannou::winit::event::WindowEvent::CloseRequested = event {
-        model.should_exit = true;
+    fn label(&self) -> &'static str {
+        match self {
+            WallMode::Reflect => "Bounce",
+            WallMode::Wrap => "Wrap around",
+            WallMode::Open => "Open",
+        }
     }
 }
 
-fn get_config_path() -> PathBuf {
-    if let Some(appdata) = dirs::config_dir() {
-        let config_dir = appdata.join("DVDScreensaver");
-        let _ = create_dir_all(&config_dir);
-        config_dir.join("config.ini")
-    } else {
-        PathBuf::from("screensaver.ini")
+impl std::fmt::Display for WallMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            WallMode::Reflect => write!(f, "reflect"),
+            WallMode::Wrap => write!(f, "wrap"),
+            WallMode::Open => write!(f, "open"),
+        }
     }
 }
 
-fn load_config() -> ScreenSaverConfig {
-    let config_path = get_config_path();
+impl std::str::FromStr for WallMode {
+    type Err = String;
 
-    if config_path.exists() {
-        if let Ok(mut file) = File::open(&config_path) {
-            let mut contents = String::new();
-            if file.read_to_string(&mut contents).is_ok() {
-                let mut lines = contents.lines();
-                let speed = lines.next().unwrap_or("50.0").parse().unwrap_or(50.0);
-                let image_index = lines.next().unwrap_or("0").parse().unwrap_or(0);
-                let size_factor = lines.next().unwrap_or("0.16").parse().unwrap_or(0.16);
-                let custom_image_path = lines.next().unwrap_or("").to_string();
-                return ScreenSaverConfig {
-                    speed,
-                    image_index,
-                    size_factor,
-                    custom_image_path,
-                };
-            }
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "reflect" => Ok(WallMode::Reflect),
+            "wrap" => Ok(WallMode::Wrap),
+            "open" => Ok(WallMode::Open),
+            _ => Err(format!("Unknown wall mode '{}'", s)),
         }
     }
+}
 
-    ScreenSaverConfig {
-        speed: 50.0,
-        image_index: 0,
-        size_factor: 0.16,
-        custom_image_path: String::new(),
+/// Which edge of the screen the banner runs along.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BannerPosition {
+    Top,
+    Bottom,
+}
+
+impl BannerPosition {
+    const ALL: [BannerPosition; 2] = [BannerPosition::Top, BannerPosition::Bottom];
+
+    fn label(&self) -> &'static str {
+        match self {
+            BannerPosition::Top => "Top",
+            BannerPosition::Bottom => "Bottom",
+        }
     }
 }
 
-fn save_config(config: &ScreenSaverConfig) {
-    let config_path = get_config_path();
+impl std::fmt::Display for BannerPosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            BannerPosition::Top => write!(f, "top"),
+            BannerPosition::Bottom => write!(f, "bottom"),
+        }
+    }
+}
 
-    if let Some(parent) = config_path.parent() {
-        let _ = create_dir_all(parent);
+impl std::str::FromStr for BannerPosition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "top" => Ok(BannerPosition::Top),
+            "bottom" => Ok(BannerPosition::Bottom),
+            _ => Err(format!("Unknown banner position '{}'", s)),
+        }
     }
+}
 
-    if let Ok(mut file) = OpenOptions::new()
-        .write(true)
-        .create(true)
-        .truncate(true)
-        .open(&config_path)
-    {
-        let _ = writeln!(file, "{}", config.speed);
-        let _ = writeln!(file, "{}", config.image_index);
-        let _ = writeln!(file, "{}", config.size_factor);
-        let _ = writeln!(file, "{}", config.custom_image_path);
+/// Which way logos start moving. A logo moving along one axis only ever hits
+/// the two walls across it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum StartAxis {
+    Diagonal,
+    Horizontal,
+    Vertical,
+}
+
+impl StartAxis {
+    const ALL: [StartAxis; 3] = [
+        StartAxis::Diagonal,
+        StartAxis::Horizontal,
+        StartAxis::Vertical,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            StartAxis::Diagonal => "Diagonally",
+            StartAxis::Horizontal => "Left and right only",
+            StartAxis::Vertical => "Up and down only",
+        }
+    }
+
+    /// `direction` with the other axis removed.
+    fn constrain(&self, direction: Vec2) -> Vec2 {
+        match self {
+            StartAxis::Diagonal => direction,
+            StartAxis::Horizontal => Vec2::new(direction.x, 0.0),
+            StartAxis::Vertical => Vec2::new(0.0, direction.y),
+        }
     }
 }
 
-fn load_image_safe(path: &str) -> Result<DynamicImage, ImageError> {
-    if path.is_empty() {
-        return Err(ImageError::IoError(std::io::Error::new(
-            std::io::ErrorKind::NotFound,
-            "Empty path",
-        )));
+impl std::fmt::Display for StartAxis {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            StartAxis::Diagonal => write!(f, "diagonal"),
+            StartAxis::Horizontal => write!(f, "horizontal"),
+            StartAxis::Vertical => write!(f, "vertical"),
+        }
     }
+}
 
-    let path = Path::new(path);
-    if !path.exists() {
-        return Err(ImageError::IoError(std::io::Error::new(
-            std::io::ErrorKind::NotFound,
-            "File not found",
-        )));
+impl std::str::FromStr for StartAxis {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "diagonal" => Ok(StartAxis::Diagonal),
+            "horizontal" => Ok(StartAxis::Horizontal),
+            "vertical" => Ok(StartAxis::Vertical),
+            _ => Err(format!("Unknown start axis '{}'", s)),
+        }
     }
+}
 
-    image::open(path)
+/// Where the first logo appears when the screensaver starts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum StartPosition {
+    Center,
+    Random,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
 }
 
-fn get_image_data(image_index: usize, custom_path: &str) -> Result<DynamicImage, String> {
-    match image_index {
-        0 => {
-            let data = include_bytes!("../assets/dvd_logo.png");
-            image::load_from_memory(data)
-                .map_err(|e| format!("Unable to load built-in icon 1: {}", e))
+impl StartPosition {
+    const ALL: [StartPosition; 6] = [
+        StartPosition::Center,
+        StartPosition::Random,
+        StartPosition::TopLeft,
+        StartPosition::TopRight,
+        StartPosition::BottomLeft,
+        StartPosition::BottomRight,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            StartPosition::Center => "Center",
+            StartPosition::Random => "Random",
+            StartPosition::TopLeft => "Top left corner",
+            StartPosition::TopRight => "Top right corner",
+            StartPosition::BottomLeft => "Bottom left corner",
+            StartPosition::BottomRight => "Bottom right corner",
         }
-        1 => {
-            let data = include_bytes!("../assets/dvd_logo2.png");
-            image::load_from_memory(data)
-                .map_err(|e| format!("Unable to load built-in icon 2: {}", e))
+    }
+
+    /// For the corners, which corner as -1/1 on each axis.
+    fn corner(&self) -> Option<Vec2> {
+        match self {
+            StartPosition::TopLeft => Some(Vec2::new(-1.0, 1.0)),
+            StartPosition::TopRight => Some(Vec2::new(1.0, 1.0)),
+            StartPosition::BottomLeft => Some(Vec2::new(-1.0, -1.0)),
+            StartPosition::BottomRight => Some(Vec2::new(1.0, -1.0)),
+            StartPosition::Center | StartPosition::Random => None,
         }
-        2 => {
-            if custom_path.is_empty() {
-                return Err("No custom icon path specified".to_string());
-            }
+    }
+}
 
-            load_image_safe(custom_path)
-                .map_err(|e| format!("Unable to load custom icon '{}': {}", custom_path, e))
+impl std::fmt::Display for StartPosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            StartPosition::Center => write!(f, "center"),
+            StartPosition::Random => write!(f, "random"),
+            StartPosition::TopLeft => write!(f, "top-left"),
+            StartPosition::TopRight => write!(f, "top-right"),
+            StartPosition::BottomLeft => write!(f, "bottom-left"),
+            StartPosition::BottomRight => write!(f, "bottom-right"),
         }
-        _ => {
-            let data = include_bytes!("../assets/dvd_logo.png");
-            image::load_from_memory(data).map_err(|e| format!("Unable to load default icon: {}", e))
+    }
+}
+
+impl std::str::FromStr for StartPosition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "center" => Ok(StartPosition::Center),
+            "random" => Ok(StartPosition::Random),
+            "top-left" => Ok(StartPosition::TopLeft),
+            "top-right" => Ok(StartPosition::TopRight),
+            "bottom-left" => Ok(StartPosition::BottomLeft),
+            "bottom-right" => Ok(StartPosition::BottomRight),
+            _ => Err(format!("Unknown start position '{}'", s)),
         }
     }
 }
 
-fn change_color(image: &DynamicImage) -> DynamicImage {
-    let mut rng = thread_rng();
-    let mut last_hue = LAST_HUE.lock().unwrap();
+/// How a background image whose aspect ratio differs from the window's is
+/// fitted: cropped to fill it, or letterboxed to show all of it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BackgroundFit {
+    Cover,
+    Contain,
+}
 
-    let mut new_hue;
-    loop {
-        new_hue = rng.gen_range(0..360);
-        let hue_diff = (new_hue - *last_hue).abs();
-        let min_diff = hue_diff.min(360 - hue_diff);
+impl BackgroundFit {
+    const ALL: [BackgroundFit; 2] = [BackgroundFit::Cover, BackgroundFit::Contain];
 
-        if min_diff >= 60 {
-            break;
+    fn label(&self) -> &'static str {
+        match self {
+            BackgroundFit::Cover => "Fill screen (crop)",
+            BackgroundFit::Contain => "Show whole image (letterbox)",
         }
     }
 
-    *last_hue = new_hue;
-    drop(last_hue);
+    /// How much to scale an image of `size` to fit it to `area`.
+    fn scale(&self, size: Vec2, area: Vec2) -> f32 {
+        let ratio = area / size;
+        match self {
+            BackgroundFit::Cover => ratio.max_element(),
+            BackgroundFit::Contain => ratio.min_element(),
+        }
+    }
+}
 
-    image.huerotate(new_hue).brighten(10).adjust_contrast(1.2)
+impl std::fmt::Display for BackgroundFit {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            BackgroundFit::Cover => write!(f, "cover"),
+            BackgroundFit::Contain => write!(f, "contain"),
+        }
+    }
 }
 
-fn model(app: &App) -> Model {
-    let _primary_window_id = app
-        .new_window()
-        .event(window_event)
-        .view(view)
-        .fullscreen()
-        .msaa_samples(4)
-        .build()
-        .unwrap();
+impl std::str::FromStr for BackgroundFit {
+    type Err = String;
 
-    let primary_window = app.window(_primary_window_id).unwrap();
-    primary_window.set_cursor_visible(false);
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "cover" => Ok(BackgroundFit::Cover),
+            "contain" => Ok(BackgroundFit::Contain),
+            _ => Err(format!("Unknown background fit '{}'", s)),
+        }
+    }
+}
 
-    let config = load_config();
+/// When the logo switches to the next image of a folder.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ImageCycle {
+    Never,
+    OnBounce,
+    OnTimer,
+}
 
-    let original_image = match get_image_data(config.image_index, &config.custom_image_path) {
-        Ok(img) => {
-            let window_rect = app.window_rect();
-            let target_width = (window_rect.w() * config.size_factor) as u32;
-            let target_height = (window_rect.h() * config.size_factor) as u32;
+impl ImageCycle {
+    const ALL: [ImageCycle; 3] = [ImageCycle::Never, ImageCycle::OnBounce, ImageCycle::OnTimer];
 
-            img.thumbnail(target_width, target_height)
+    fn label(&self) -> &'static str {
+        match self {
+            ImageCycle::Never => "Never",
+            ImageCycle::OnBounce => "On every bounce",
+            ImageCycle::OnTimer => "On a timer",
         }
-        Err(error) => {
-            eprintln!("Icon loading failed: {}, using default icon", error);
+    }
+}
 
-            let data = include_bytes!("../assets/dvd_logo.png");
-            let default_img = image::load_from_memory(data).expect("Unable to load default icon");
+impl std::fmt::Display for ImageCycle {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ImageCycle::Never => write!(f, "never"),
+            ImageCycle::OnBounce => write!(f, "bounce"),
+            ImageCycle::OnTimer => write!(f, "timer"),
+        }
+    }
+}
 
-            let window_rect = app.window_rect();
-            let target_width = (window_rect.w() * config.size_factor) as u32;
-            let target_height = (window_rect.h() * config.size_factor) as u32;
+impl std::str::FromStr for ImageCycle {
+    type Err = String;
 
-            default_img.thumbnail(target_width, target_height)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "never" => Ok(ImageCycle::Never),
+            "bounce" => Ok(ImageCycle::OnBounce),
+            "timer" => Ok(ImageCycle::OnTimer),
+            _ => Err(format!("Unknown image cycle '{}'", s)),
         }
+    }
+}
+
+/// How the logo's color changes on each bounce.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ColorMode {
+    /// Rotate the icon's hues to a random point on the color wheel.
+    Rotate,
+    /// Tint the icon with a hue within `variation` degrees of `base`'s.
+    Tint { base: [u8; 3], variation: f32 },
+    /// Rotate the icon's hues continuously, `speed` degrees per second.
+    RainbowCycle { speed: f32 },
+    /// Keep the hue, but flash up to `intensity` of the way to white.
+    BrightnessPulse { intensity: f32 },
+}
+
+impl ColorMode {
+    const DEFAULT_TINT: ColorMode = ColorMode::Tint {
+        base: [40, 120, 255],
+        variation: 30.0,
     };
+    const DEFAULT_RAINBOW: ColorMode = ColorMode::RainbowCycle { speed: 60.0 };
+    const DEFAULT_PULSE: ColorMode = ColorMode::BrightnessPulse { intensity: 0.6 };
+}
+
+impl std::fmt::Display for ColorMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ColorMode::Rotate => write!(f, "rotate"),
+            ColorMode::Tint {
+                base: [r, g, b],
+                variation,
+            } => write!(f, "tint:{}:{}", format_hex_color([*r, *g, *b]), variation),
+            ColorMode::RainbowCycle { speed } => write!(f, "rainbow:{}", speed),
+            ColorMode::BrightnessPulse { intensity } => write!(f, "pulse:{}", intensity),
+        }
+    }
+}
 
-    let image = change_color(&original_image);
+impl std::str::FromStr for ColorMode {
+    type Err = String;
 
-    let rect = Rect::from_x_y_w_h(
-        0.0,
-        0.0,
-        image.dimensions().0 as f32,
-        image.dimensions().1 as f32,
-    );
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Unknown color mode '{}'", s);
 
-    Model {
-        image,
-        original_image,
-        dvd_rect: rect,
-        dvd_vel: Vec2::new(config.speed, config.speed),
-        m_pos: None,
-        is_preview: false,
-        preview_parent: None,
+        match s.split(':').collect::<Vec<_>>()[..] {
+            ["rotate"] => Ok(ColorMode::Rotate),
+            ["tint", hex, variation] => Ok(ColorMode::Tint {
+                base: parse_hex_color(hex).ok_or_else(invalid)?,
+                variation: variation.parse().map_err(|_| invalid())?,
+            }),
+            ["rainbow", speed] => Ok(ColorMode::RainbowCycle {
+                speed: speed.parse().map_err(|_| invalid())?,
+            }),
+            ["pulse", intensity] => Ok(ColorMode::BrightnessPulse {
+                intensity: intensity.parse().map_err(|_| invalid())?,
+            }),
+            _ => Err(invalid()),
+        }
     }
 }
 
-fn window_event(app: &App, model: &mut Model, event: WindowEvent) {
-    if model.is_preview {
-        return;
+#[derive(Clone)]
+struct ScreenSaverConfig {
+    speed: f32,
+    image_index: usize,
+    size_factor: f32,
+    custom_image_path: String,
+    logo_count: usize,
+    speed_variance: f32,
+    logo_collision: bool,
+    shadow: bool,
+    shadow_offset: f32,
+    shadow_opacity: f32,
+    glow: bool,
+    glow_intensity: f32,
+    scale_filter: ScaleFilter,
+    hide_cursor: bool,
+    idle_timeout_secs: u32,
+    wall_margin: f32,
+    logo_opacity: f32,
+    brightness: i32,
+    contrast: f32,
+    saturation: f32,
+    invert: bool,
+    size_mode: SizeMode,
+    randomize_on_launch: bool,
+    /// Indexed by `Edge`: left, right, bottom, top.
+    walls: [WallMode; 4],
+    /// When set, `speed` is used on both axes and `speed_x`/`speed_y` are
+    /// ignored.
+    link_speeds: bool,
+    speed_x: f32,
+    speed_y: f32,
+    start_position: StartPosition,
+    /// Anti-aliasing samples for the screensaver window; 0 and 1 both mean off.
+    msaa_samples: u32,
+    background_image_path: String,
+    background_fit: BackgroundFit,
+    show_bounce_counter: bool,
+    milestone_effects: bool,
+    /// Makes the colors and random positions the same on every run.
+    seed: Option<u64>,
+    easing: bool,
+    easing_strength: f32,
+    color_mode: ColorMode,
+    /// Exit on its own after this long, for kiosk displays.
+    max_runtime_secs: Option<u32>,
+    outline: bool,
+    outline_color: [u8; 3],
+    outline_thickness: f32,
+    /// Mirror the logo so it faces its horizontal direction of travel.
+    flip_on_bounce: bool,
+    /// If the custom icon path is a folder, show its newest image and switch
+    /// to newer ones as they are added.
+    watch_folder: bool,
+    /// If the custom icon path is a folder, step through its images.
+    image_cycle: ImageCycle,
+    image_cycle_secs: f32,
+    /// A blurred glow that hugs the logo's silhouette.
+    neon_glow: bool,
+    neon_glow_radius: f32,
+    neon_glow_follows_logo: bool,
+    neon_glow_color: [u8; 3],
+    /// Squash the logo against walls it hits, cartoon style.
+    squash: bool,
+    squash_intensity: f32,
+    /// Dim everything between `night_start_hour` and `night_end_hour`, local time.
+    night_mode: bool,
+    night_brightness: f32,
+    night_start_hour: u32,
+    night_end_hour: u32,
+    /// Leave fading copies of the logo where it last bounced.
+    echoes: bool,
+    echo_count: usize,
+    echo_fade_secs: f32,
+    /// Hold the logo still in the corner for a moment after a corner hit.
+    freeze_on_corner: bool,
+    freeze_secs: f32,
+    /// The hue of the first color shown, instead of a random one.
+    initial_hue: Option<i32>,
+    /// Bounces within this long of a color change don't change it again, so
+    /// grazing a corner doesn't flicker through two colors.
+    recolor_cooldown_secs: f32,
+    /// How far, in points, the mouse has to move within
+    /// `MOUSE_EXIT_WINDOW` to exit, so sensor jitter doesn't.
+    mouse_exit_distance: f32,
+    /// Render at this height and upscale, instead of at the native resolution.
+    internal_height: Option<u32>,
+    /// Give each logo a different image, round-robin from the built-in icons or
+    /// the custom icon's folder.
+    varied_logos: bool,
+    /// Bounce off the walls and other logos with the visible pixels of the logo
+    /// rather than its whole image.
+    tight_collision: bool,
+    /// How long the screen takes to fade in from black at startup, or 0 for no
+    /// fade.
+    startup_fade_secs: f32,
+    /// The part of the window the logos bounce in, as fractions of its size,
+    /// and how far its center is from the window's.
+    region_width: f32,
+    region_height: f32,
+    region_x: f32,
+    region_y: f32,
+    region_border: bool,
+    /// Ignore the mouse and every key but Escape, for unattended displays.
+    exit_only_on_escape: bool,
+    /// Start with one logo and add another this often, up to `max_logos`, or 0
+    /// to start with `logo_count` logos.
+    spawn_interval_secs: f32,
+    max_logos: usize,
+    start_axis: StartAxis,
+    /// Strobe through colors for the first moments after starting.
+    attract_mode: bool,
+    /// A block the logos bounce off, as fractions of the window's size and of
+    /// how far its center is from the window's.
+    obstacle: bool,
+    obstacle_width: f32,
+    obstacle_height: f32,
+    obstacle_x: f32,
+    obstacle_y: f32,
+    /// Shrink and fade out before quitting instead of closing at once.
+    exit_animation: bool,
+    /// Show a toast and a burst of confetti on corner hits.
+    corner_celebration: bool,
+    /// Slowly drift the whole scene by a few pixels to spread wear on OLED
+    /// and plasma panels.
+    pixel_shift: bool,
+    pixel_shift_amount: f32,
+    pixel_shift_period_secs: f32,
+    /// The icons shown when `image_index` is `ICON_SET_INDEX`, in order.
+    icon_set: Vec<IconSource>,
+    /// A point that pulls the logos' paths towards it, as fractions of how far
+    /// it is from the window's center.
+    gravity_well: bool,
+    gravity_well_x: f32,
+    gravity_well_y: f32,
+    gravity_well_strength: f32,
+    /// How many megabytes of loaded and recolored images to keep for reuse, or
+    /// 0 to always redo the work.
+    image_cache_mb: u32,
+    /// A message shown in a bar across the screen, e.g. who the display belongs
+    /// to, or empty for none.
+    banner_text: String,
+    banner_color: [u8; 3],
+    banner_position: BannerPosition,
+    /// Update once per displayed frame instead of at a fixed 60 Hz, so the
+    /// logos move equally smoothly at the same speed on any refresh rate.
+    normalize_speed: bool,
+    /// Fills the screen with the image and slowly pans and zooms it instead of
+    /// bouncing logos.
+    photo_frame: bool,
+    /// How long each pan and zoom of the photo frame takes.
+    photo_pan_secs: f32,
+    photo_zoom_min: f32,
+    photo_zoom_max: f32,
+    photo_recolor: bool,
+}
+
+impl ScreenSaverConfig {
+    fn uses_icon_set(&self) -> bool {
+        self.image_index == ICON_SET_INDEX && !self.icon_set.is_empty()
     }
 
-    if app.time > 0.1 {
-        match event {
-            WindowEvent::MouseMoved(pos) => {
-                if model.m_pos.is_none() {
-                    model.m_pos = Some(pos);
-                }
-                if model.m_pos.unwrap() != pos {
-                    app.quit();
-                }
+    /// The icon the first logo shows. An empty icon set falls back to the
+    /// first built-in icon.
+    fn icon_source(&self) -> IconSource {
+        match self.image_index {
+            index if index < BUILT_IN_ICON_COUNT => IconSource::BuiltIn(index),
+            ICON_SET_INDEX => self.icon_set.first().cloned().unwrap_or_default(),
+            _ => IconSource::Custom(PathBuf::from(&self.custom_image_path)),
+        }
+    }
+
+    /// `msaa_samples` rounded to a sample count the window can be built with.
+    fn sample_count(&self) -> u32 {
+        self.msaa_samples.clamp(1, 8).next_power_of_two()
+    }
+
+    /// The bounce region as fractions of the window, moved back inside it if
+    /// the offset would push it past an edge.
+    fn bounce_region(&self) -> Rect {
+        let size =
+            Vec2::new(self.region_width, self.region_height).clamp(Vec2::splat(0.1), Vec2::ONE);
+        let limit = (Vec2::ONE - size) / 2.0;
+        let center = Vec2::new(self.region_x, self.region_y).clamp(-limit, limit);
+        Rect::from_xy_wh(center, size)
+    }
+
+    /// The obstacle as fractions of the window, if enabled.
+    fn obstacle_rect(&self) -> Option<Rect> {
+        self.obstacle.then(|| {
+            Rect::from_x_y_w_h(
+                self.obstacle_x,
+                self.obstacle_y,
+                self.obstacle_width.clamp(0.05, 0.8),
+                self.obstacle_height.clamp(0.05, 0.8),
+            )
+        })
+    }
+
+    /// The gravity well's position as fractions of the window, and its
+    /// strength, if enabled.
+    fn gravity_well(&self) -> Option<(Vec2, f32)> {
+        self.gravity_well.then(|| {
+            (
+                Vec2::new(self.gravity_well_x, self.gravity_well_y),
+                self.gravity_well_strength.clamp(0.01, 0.5),
+            )
+        })
+    }
+
+    /// How many logos there are at most, once any have been added over time.
+    fn max_logo_count(&self) -> usize {
+        if self.spawn_interval_secs > 0.0 {
+            self.max_logos.max(1)
+        } else {
+            self.logo_count.max(1)
+        }
+    }
+
+    /// The horizontal and vertical speed in points per second.
+    fn axis_speeds(&self) -> Vec2 {
+        if self.link_speeds {
+            Vec2::splat(self.speed)
+        } else {
+            Vec2::new(self.speed_x, self.speed_y)
+        }
+    }
+
+    /// The box the icon is scaled to fit inside when drawn on a surface of
+    /// `area` points. `scale` shrinks fixed pixel sizes for miniature previews.
+    fn icon_target_size(&self, area: Vec2, scale: f32) -> (u32, u32) {
+        if self.photo_frame {
+            return (area.x as u32, area.y as u32);
+        }
+
+        match self.size_mode {
+            SizeMode::Fraction => (
+                (area.x * self.size_factor) as u32,
+                (area.y * self.size_factor) as u32,
+            ),
+            SizeMode::Pixels(pixels) => {
+                let edge = (pixels as f32 * scale) as u32;
+                (edge, edge)
             }
-            WindowEvent::MousePressed(..)
-            | WindowEvent::KeyPressed(..)
-            | WindowEvent::MouseWheel(..) => app.quit(),
-            _ => (),
         }
     }
 }
 
-fn update(app: &App, model: &mut Model, _update: Update) {
-    let win = app.window_rect();
-    let delta_time = app.duration.since_prev_update.secs() as f32;
-    let dvd_vel = &mut model.dvd_vel;
+impl Default for ScreenSaverConfig {
+    fn default() -> Self {
+        ScreenSaverConfig {
+            speed: 50.0,
+            image_index: 0,
+            size_factor: 0.16,
+            custom_image_path: String::new(),
+            logo_count: 1,
+            speed_variance: 0.0,
+            logo_collision: false,
+            shadow: false,
+            shadow_offset: 6.0,
+            shadow_opacity: 0.4,
+            glow: false,
+            glow_intensity: 0.5,
+            scale_filter: ScaleFilter::Smooth,
+            hide_cursor: true,
+            idle_timeout_secs: 300,
+            wall_margin: 0.0,
+            logo_opacity: 1.0,
+            brightness: 10,
+            contrast: 1.2,
+            saturation: 1.0,
+            invert: false,
+            size_mode: SizeMode::Fraction,
+            randomize_on_launch: false,
+            walls: [WallMode::Reflect; 4],
+            link_speeds: true,
+            speed_x: 50.0,
+            speed_y: 50.0,
+            start_position: StartPosition::Center,
+            msaa_samples: 4,
+            background_image_path: String::new(),
+            background_fit: BackgroundFit::Cover,
+            show_bounce_counter: false,
+            milestone_effects: false,
+            seed: None,
+            easing: false,
+            easing_strength: 0.5,
+            color_mode: ColorMode::Rotate,
+            max_runtime_secs: None,
+            outline: false,
+            outline_color: [255, 255, 255],
+            outline_thickness: 2.0,
+            flip_on_bounce: false,
+            watch_folder: false,
+            image_cycle: ImageCycle::Never,
+            image_cycle_secs: 10.0,
+            neon_glow: false,
+            neon_glow_radius: 12.0,
+            neon_glow_follows_logo: true,
+            neon_glow_color: [0, 255, 200],
+            squash: false,
+            squash_intensity: 0.3,
+            night_mode: false,
+            night_brightness: 0.4,
+            night_start_hour: 22,
+            night_end_hour: 7,
+            echoes: false,
+            echo_count: 4,
+            echo_fade_secs: 3.0,
+            freeze_on_corner: false,
+            freeze_secs: 1.0,
+            initial_hue: None,
+            recolor_cooldown_secs: 0.15,
+            mouse_exit_distance: 20.0,
+            internal_height: None,
+            varied_logos: false,
+            tight_collision: false,
+            startup_fade_secs: 0.0,
+            region_width: 1.0,
+            region_height: 1.0,
+            region_x: 0.0,
+            region_y: 0.0,
+            region_border: false,
+            exit_only_on_escape: false,
+            spawn_interval_secs: 0.0,
+            max_logos: 10,
+            start_axis: StartAxis::Diagonal,
+            attract_mode: false,
+            obstacle: false,
+            obstacle_width: 0.2,
+            obstacle_height: 0.2,
+            obstacle_x: 0.0,
+            obstacle_y: 0.0,
+            exit_animation: false,
+            corner_celebration: false,
+            pixel_shift: false,
+            pixel_shift_amount: 4.0,
+            pixel_shift_period_secs: 120.0,
+            icon_set: Vec::new(),
+            gravity_well: false,
+            gravity_well_x: 0.0,
+            gravity_well_y: 0.0,
+            gravity_well_strength: 0.1,
+            image_cache_mb: 64,
+            banner_text: String::new(),
+            banner_color: [20, 20, 20],
+            banner_position: BannerPosition::Top,
+            normalize_speed: false,
+            photo_frame: false,
+            photo_pan_secs: 20.0,
+            photo_zoom_min: 1.0,
+            photo_zoom_max: 1.3,
+            photo_recolor: false,
+        }
+    }
+}
 
-    let new_x = model.dvd_rect.x() + dvd_vel.x * delta_time;
-    let new_y = model.dvd_rect.y() + dvd_vel.y * delta_time;
+fn main() {
+    let mut args: Vec<String> = env::args().collect();
 
-    model.dvd_rect = Rect::from_x_y_w_h(new_x, new_y, model.dvd_rect.w(), model.dvd_rect.h());
+    let options = take_options(&mut args);
+    rotate_log();
+    if let Err(error) = options {
+        exit_with_argument_error(&error);
+    }
+    log_message!("Starting with arguments {:?}", &args[1..]);
 
-    let mut color_changed = false;
+    // Anything the handling in `build_window` doesn't cover still ends up in
+    // the log rather than only on a console release builds don't have.
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        log_message!("Panicked: {}", info);
+        default_hook(info);
+    }));
 
-    if model.dvd_rect.left() <= win.left() {
-        model.dvd_rect = Rect::from_x_y_w_h(
-            win.left() + model.dvd_rect.w() / 2.0,
-            model.dvd_rect.y(),
-            model.dvd_rect.w(),
-            model.dvd_rect.h(),
-        );
-        dvd_vel.x = dvd_vel.x.abs();
-        color_changed = true;
+    if args.iter().any(|arg| arg == "--daemon") {
+        run_idle_daemon();
+        return;
     }
 
-    if model.dvd_rect.right() >= win.right() {
-        model.dvd_rect = Rect::from_x_y_w_h(
-            win.right() - model.dvd_rect.w() / 2.0,
-            model.dvd_rect.y(),
-            model.dvd_rect.w(),
-            model.dvd_rect.h(),
-        );
-        dvd_vel.x = -dvd_vel.x.abs();
-        color_changed = true;
+    if args.iter().any(|arg| arg == "--dump-config") {
+        dump_config();
+        return;
     }
 
-    if model.dvd_rect.bottom() <= win.bottom() {
-        model.dvd_rect = Rect::from_x_y_w_h(
-            model.dvd_rect.x(),
-            win.bottom() + model.dvd_rect.h() / 2.0,
-            model.dvd_rect.w(),
-            model.dvd_rect.h(),
-        );
-        dvd_vel.y = dvd_vel.y.abs();
-        color_changed = true;
+    if args
+        .iter()
+        .any(|arg| arg == "--install" || arg == "--uninstall")
+    {
+        let result = if args.iter().any(|arg| arg == "--install") {
+            install_screensaver()
+        } else {
+            uninstall_screensaver()
+        };
+        match result {
+            Ok(message) => {
+                log_message!("{}", message);
+                println!("{}", message);
+            }
+            Err(error) => {
+                log_message!("{}", error);
+                eprintln!("{}", error);
+                std::process::exit(1);
+            }
+        }
+        return;
     }
 
-    if model.dvd_rect.top() >= win.top() {
-        model.dvd_rect = Rect::from_x_y_w_h(
-            model.dvd_rect.x(),
-            win.top() - model.dvd_rect.h() / 2.0,
-            model.dvd_rect.w(),
-            model.dvd_rect.h(),
-        );
-        dvd_vel.y = -dvd_vel.y.abs();
-        color_changed = true;
+    if args.len() == 1 {
+        run_screensaver();
+        return;
     }
 
-    if color_changed {
-        model.image = change_color(&model.original_image);
+    let flag = &args[1].to_lowercase();
+
+    if flag.starts_with("/c") || flag.starts_with("-c") {
+        show_configuration_dialog();
+    } else if flag.starts_with("/p") || flag.starts_with("-p") {
+        let hwnd = parse_preview_hwnd(&args);
+        run_preview_mode(hwnd);
+    } else if flag.starts_with("/s") || flag.starts_with("-s") {
+        run_screensaver();
+    } else if flag.starts_with("/a") || flag.starts_with("-a") {
+        std::process::exit(0);
+    } else if flag.starts_with("/w") || flag.starts_with("-w") {
+        WINDOWED_MODE.store(true, Ordering::SeqCst);
+        run_screensaver();
+    } else {
+        run_screensaver();
     }
 }
 
-fn view(app: &App, model: &Model, frame: Frame) {
-    frame.clear(BLACK);
+#[cfg(windows)]
+const DESKTOP_REGISTRY_KEY: &str = r"HKCU\Control Panel\Desktop";
+
+/// Where `--install` puts the screensaver, so it shows up in the Windows
+/// screen saver settings.
+#[cfg(windows)]
+fn installed_screensaver_path() -> PathBuf {
+    let system_root = env::var("SystemRoot").unwrap_or_else(|_| r"C:\Windows".to_string());
+    Path::new(&system_root)
+        .join("System32")
+        .join("DVDScreensaver.scr")
+}
+
+/// Copies this executable into System32 as a `.scr` and makes it the current
+/// user's screensaver.
+#[cfg(windows)]
+fn install_screensaver() -> Result<String, String> {
+    let exe = env::current_exe().map_err(|e| format!("Unable to locate executable: {}", e))?;
+    let target = installed_screensaver_path();
 
-    let draw = app.draw();
-    let texture = wgpu::Texture::from_image(app, &model.image);
+    std::fs::copy(&exe, &target).map_err(|e| {
+        format!(
+            "Unable to copy to {}: {} (try running as administrator)",
+            target.display(),
+            e
+        )
+    })?;
 
-    draw.texture(&texture)
-        .xy(model.dvd_rect.xy())
-        .wh(model.dvd_rect.wh());
+    let registered = std::process::Command::new("reg")
+        .args([
+            "add",
+            DESKTOP_REGISTRY_KEY,
+            "/v",
+            "SCRNSAVE.EXE",
+            "/t",
+            "REG_SZ",
+        ])
+        .arg("/d")
+        .arg(&target)
+        .arg("/f")
+        .status()
+        .is_ok_and(|status| status.success());
 
-    draw.to_frame(app, &frame).unwrap();
+    if registered {
+        Ok(format!(
+            "Installed to {} and set as the screensaver",
+            target.display()
+        ))
+    } else {
+        Ok(format!(
+            "Installed to {}; select it in the screen saver settings",
+            target.display()
+        ))
+    }
+}
+
+/// Removes the `.scr` installed by `--install`, and unsets it as the current
+/// user's screensaver if it still is.
+#[cfg(windows)]
+fn uninstall_screensaver() -> Result<String, String> {
+    let target = installed_screensaver_path();
+
+    if target.exists() {
+        std::fs::remove_file(&target).map_err(|e| {
+            format!(
+                "Unable to remove {}: {} (try running as administrator)",
+                target.display(),
+                e
+            )
+        })?;
+    }
+
+    let is_registered = std::process::Command::new("reg")
+        .args(["query", DESKTOP_REGISTRY_KEY, "/v", "SCRNSAVE.EXE"])
+        .output()
+        .is_ok_and(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .to_lowercase()
+                .contains(&target.to_string_lossy().to_lowercase())
+        });
+    if is_registered {
+        let _ = std::process::Command::new("reg")
+            .args(["delete", DESKTOP_REGISTRY_KEY, "/v", "SCRNSAVE.EXE", "/f"])
+            .status();
+    }
+
+    Ok(format!("Removed {}", target.display()))
+}
+
+#[cfg(not(windows))]
+fn install_screensaver() -> Result<String, String> {
+    Ok(
+        "Installing is only supported on Windows; elsewhere, use --daemon to start it when idle"
+            .to_string(),
+    )
+}
+
+#[cfg(not(windows))]
+fn uninstall_screensaver() -> Result<String, String> {
+    Ok("Nothing to uninstall: installing is only supported on Windows".to_string())
+}
+
+/// Builds a window, or logs why it couldn't be and exits instead of panicking,
+/// e.g. when there's no usable GPU. Later GPU errors, like the device being
+/// lost when a laptop switches GPUs or the driver resets, exit with a logged
+/// message too rather than wgpu's default panic. A lost or outdated surface is
+/// already reconfigured, or the frame skipped, by nannou.
+fn build_window(app: &App, builder: nannou::window::Builder) -> nannou::window::Id {
+    let id = match builder.build() {
+        Ok(id) => id,
+        Err(error) => {
+            log_message!("Unable to create a window: {}", error);
+            std::process::exit(1);
+        }
+    };
+
+    if let Some(window) = app.window(id) {
+        window.device().on_uncaptured_error(Box::new(|error| {
+            log_message!("GPU error, exiting: {}", error);
+            std::process::exit(1);
+        }));
+    }
+    id
+}
+
+/// Runs the screensaver at a fixed 60 updates per second, or at the fastest
+/// monitor's refresh rate with `normalize_speed`, which `model` switches to.
+/// Every launch path
+/// goes through here so `/s`, which is how Windows starts it, behaves exactly
+/// like a launch without arguments.
+fn run_screensaver() {
+    nannou::app(model)
+        .update(update)
+        .exit(exit)
+        .loop_mode(nannou::LoopMode::Rate {
+            update_interval: std::time::Duration::from_secs_f64(1.0 / 60.0),
+        })
+        .run();
+}
+
+/// Prints the effective configuration as `key=value` lines, for checking which
+/// settings are actually in effect.
+fn dump_config() {
+    let profile = active_profile();
+    println!("profile={}", profile.as_deref().unwrap_or(DEFAULT_PROFILE));
+    println!(
+        "config_path={}",
+        get_profile_path(profile.as_deref()).display()
+    );
+    let _ = write_config_dump(&mut std::io::stdout(), &load_effective_config());
+}
+
+/// Writes each setting as `key=value`, the names `--set` accepts.
+fn write_config_dump(out: &mut impl Write, config: &ScreenSaverConfig) -> std::io::Result<()> {
+    writeln!(out, "speed={}", config.speed)?;
+    writeln!(out, "image_index={}", config.image_index)?;
+    writeln!(out, "size_factor={}", config.size_factor)?;
+    writeln!(out, "custom_image_path={}", config.custom_image_path)?;
+    writeln!(out, "logo_count={}", config.logo_count)?;
+    writeln!(out, "speed_variance={}", config.speed_variance)?;
+    writeln!(out, "logo_collision={}", config.logo_collision)?;
+    writeln!(out, "shadow={}", config.shadow)?;
+    writeln!(out, "shadow_offset={}", config.shadow_offset)?;
+    writeln!(out, "shadow_opacity={}", config.shadow_opacity)?;
+    writeln!(out, "glow={}", config.glow)?;
+    writeln!(out, "glow_intensity={}", config.glow_intensity)?;
+    writeln!(out, "scale_filter={}", config.scale_filter)?;
+    writeln!(out, "hide_cursor={}", config.hide_cursor)?;
+    writeln!(out, "idle_timeout_secs={}", config.idle_timeout_secs)?;
+    writeln!(out, "wall_margin={}", config.wall_margin)?;
+    writeln!(out, "logo_opacity={}", config.logo_opacity)?;
+    writeln!(out, "brightness={}", config.brightness)?;
+    writeln!(out, "contrast={}", config.contrast)?;
+    writeln!(out, "saturation={}", config.saturation)?;
+    writeln!(out, "invert={}", config.invert)?;
+    writeln!(out, "size_mode={}", config.size_mode)?;
+    writeln!(out, "randomize_on_launch={}", config.randomize_on_launch)?;
+    for edge in Edge::ALL {
+        writeln!(out, "wall_{}={}", edge.name(), config.walls[edge as usize])?;
+    }
+    writeln!(out, "link_speeds={}", config.link_speeds)?;
+    writeln!(out, "speed_x={}", config.speed_x)?;
+    writeln!(out, "speed_y={}", config.speed_y)?;
+    writeln!(out, "start_position={}", config.start_position)?;
+    writeln!(out, "msaa_samples={}", config.msaa_samples)?;
+    writeln!(
+        out,
+        "background_image_path={}",
+        config.background_image_path
+    )?;
+    writeln!(out, "background_fit={}", config.background_fit)?;
+    writeln!(out, "show_bounce_counter={}", config.show_bounce_counter)?;
+    writeln!(out, "milestone_effects={}", config.milestone_effects)?;
+    writeln!(
+        out,
+        "seed={}",
+        config.seed.map(|seed| seed.to_string()).unwrap_or_default()
+    )?;
+    writeln!(out, "easing={}", config.easing)?;
+    writeln!(out, "easing_strength={}", config.easing_strength)?;
+    writeln!(out, "color_mode={}", config.color_mode)?;
+    writeln!(
+        out,
+        "max_runtime_secs={}",
+        config
+            .max_runtime_secs
+            .map(|secs| secs.to_string())
+            .unwrap_or_default()
+    )?;
+    writeln!(out, "outline={}", config.outline)?;
+    writeln!(
+        out,
+        "outline_color={}",
+        format_hex_color(config.outline_color)
+    )?;
+    writeln!(out, "outline_thickness={}", config.outline_thickness)?;
+    writeln!(out, "flip_on_bounce={}", config.flip_on_bounce)?;
+    writeln!(out, "watch_folder={}", config.watch_folder)?;
+    writeln!(out, "image_cycle={}", config.image_cycle)?;
+    writeln!(out, "image_cycle_secs={}", config.image_cycle_secs)?;
+    writeln!(out, "neon_glow={}", config.neon_glow)?;
+    writeln!(out, "neon_glow_radius={}", config.neon_glow_radius)?;
+    writeln!(
+        out,
+        "neon_glow_follows_logo={}",
+        config.neon_glow_follows_logo
+    )?;
+    writeln!(
+        out,
+        "neon_glow_color={}",
+        format_hex_color(config.neon_glow_color)
+    )?;
+    writeln!(out, "squash={}", config.squash)?;
+    writeln!(out, "squash_intensity={}", config.squash_intensity)?;
+    writeln!(out, "night_mode={}", config.night_mode)?;
+    writeln!(out, "night_brightness={}", config.night_brightness)?;
+    writeln!(out, "night_start_hour={}", config.night_start_hour)?;
+    writeln!(out, "night_end_hour={}", config.night_end_hour)?;
+    writeln!(out, "echoes={}", config.echoes)?;
+    writeln!(out, "echo_count={}", config.echo_count)?;
+    writeln!(out, "echo_fade_secs={}", config.echo_fade_secs)?;
+    writeln!(out, "freeze_on_corner={}", config.freeze_on_corner)?;
+    writeln!(out, "freeze_secs={}", config.freeze_secs)?;
+    writeln!(
+        out,
+        "initial_hue={}",
+        config
+            .initial_hue
+            .map(|hue| hue.to_string())
+            .unwrap_or_default()
+    )?;
+    writeln!(
+        out,
+        "recolor_cooldown_secs={}",
+        config.recolor_cooldown_secs
+    )?;
+    writeln!(out, "mouse_exit_distance={}", config.mouse_exit_distance)?;
+    writeln!(
+        out,
+        "internal_height={}",
+        config
+            .internal_height
+            .map(|height| height.to_string())
+            .unwrap_or_default()
+    )?;
+    writeln!(out, "varied_logos={}", config.varied_logos)?;
+    writeln!(out, "tight_collision={}", config.tight_collision)?;
+    writeln!(out, "startup_fade_secs={}", config.startup_fade_secs)?;
+    writeln!(out, "region_width={}", config.region_width)?;
+    writeln!(out, "region_height={}", config.region_height)?;
+    writeln!(out, "region_x={}", config.region_x)?;
+    writeln!(out, "region_y={}", config.region_y)?;
+    writeln!(out, "region_border={}", config.region_border)?;
+    writeln!(out, "exit_only_on_escape={}", config.exit_only_on_escape)?;
+    writeln!(out, "spawn_interval_secs={}", config.spawn_interval_secs)?;
+    writeln!(out, "max_logos={}", config.max_logos)?;
+    writeln!(out, "start_axis={}", config.start_axis)?;
+    writeln!(out, "attract_mode={}", config.attract_mode)?;
+    writeln!(out, "obstacle={}", config.obstacle)?;
+    writeln!(out, "obstacle_width={}", config.obstacle_width)?;
+    writeln!(out, "obstacle_height={}", config.obstacle_height)?;
+    writeln!(out, "obstacle_x={}", config.obstacle_x)?;
+    writeln!(out, "obstacle_y={}", config.obstacle_y)?;
+    writeln!(out, "exit_animation={}", config.exit_animation)?;
+    writeln!(out, "corner_celebration={}", config.corner_celebration)?;
+    writeln!(out, "pixel_shift={}", config.pixel_shift)?;
+    writeln!(out, "pixel_shift_amount={}", config.pixel_shift_amount)?;
+    writeln!(
+        out,
+        "pixel_shift_period_secs={}",
+        config.pixel_shift_period_secs
+    )?;
+    let icon_set: Vec<String> = config.icon_set.iter().map(ToString::to_string).collect();
+    writeln!(out, "icon_set={}", icon_set.join("|"))?;
+    writeln!(out, "gravity_well={}", config.gravity_well)?;
+    writeln!(out, "gravity_well_x={}", config.gravity_well_x)?;
+    writeln!(out, "gravity_well_y={}", config.gravity_well_y)?;
+    writeln!(
+        out,
+        "gravity_well_strength={}",
+        config.gravity_well_strength
+    )?;
+    writeln!(out, "image_cache_mb={}", config.image_cache_mb)?;
+    writeln!(out, "banner_text={}", config.banner_text)?;
+    writeln!(
+        out,
+        "banner_color={}",
+        format_hex_color(config.banner_color)
+    )?;
+    writeln!(out, "banner_position={}", config.banner_position)?;
+    writeln!(out, "normalize_speed={}", config.normalize_speed)?;
+    writeln!(out, "photo_frame={}", config.photo_frame)?;
+    writeln!(out, "photo_pan_secs={}", config.photo_pan_secs)?;
+    writeln!(out, "photo_zoom_min={}", config.photo_zoom_min)?;
+    writeln!(out, "photo_zoom_max={}", config.photo_zoom_max)?;
+    writeln!(out, "photo_recolor={}", config.photo_recolor)?;
+    Ok(())
+}
+
+/// The active profile's config with any `--set` overrides applied.
+fn load_effective_config() -> ScreenSaverConfig {
+    let mut config = load_config(active_profile().as_deref());
+    for (key, value) in CONFIG_OVERRIDES.lock().unwrap().iter() {
+        // Already validated in `main`.
+        let _ = apply_override(&mut config, key, value);
+    }
+    config
+}
+
+fn parse_optional_override<T: std::str::FromStr>(
+    key: &str,
+    value: &str,
+) -> Result<Option<T>, String> {
+    if value.is_empty() {
+        Ok(None)
+    } else {
+        parse_override(key, value).map(Some)
+    }
+}
+
+fn parse_override<T: std::str::FromStr>(key: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Invalid value '{}' for {}", value, key))
+}
+
+/// Sets the field named `key`, as printed by `--dump-config`, from `value`.
+/// Numbers are clamped to the ranges the config dialog allows.
+fn apply_override(config: &mut ScreenSaverConfig, key: &str, value: &str) -> Result<(), String> {
+    macro_rules! set {
+        ($field:ident) => {
+            config.$field = parse_override(key, value)?
+        };
+        ($field:ident, $range:expr) => {{
+            config.$field = parse_override(key, value)?;
+            config.$field = config.$field.clamp(*$range.start(), *$range.end());
+        }};
+    }
+    match key {
+        "speed" => set!(speed, 10.0..=200.0),
+        "image_index" => set!(image_index, 0..=ICON_SET_INDEX),
+        "size_factor" => set!(size_factor, 0.05..=0.5),
+        "custom_image_path" => config.custom_image_path = value.to_string(),
+        "logo_count" => set!(logo_count, 1..=20),
+        "speed_variance" => set!(speed_variance, 0.0..=50.0),
+        "logo_collision" => set!(logo_collision),
+        "shadow" => set!(shadow),
+        "shadow_offset" => set!(shadow_offset, 1.0..=30.0),
+        "shadow_opacity" => set!(shadow_opacity, 0.05..=1.0),
+        "glow" => set!(glow),
+        "glow_intensity" => set!(glow_intensity, 0.1..=1.0),
+        "scale_filter" => set!(scale_filter),
+        "hide_cursor" => set!(hide_cursor),
+        "idle_timeout_secs" => set!(idle_timeout_secs, 10..=3600),
+        "wall_margin" => set!(wall_margin, 0.0..=200.0),
+        "logo_opacity" => set!(logo_opacity, 0.05..=1.0),
+        "brightness" => set!(brightness, -100..=100),
+        "contrast" => set!(contrast, -50.0..=100.0),
+        "saturation" => set!(saturation, 0.0..=2.0),
+        "invert" => set!(invert),
+        "size_mode" => set!(size_mode),
+        "randomize_on_launch" => set!(randomize_on_launch),
+        "wall_left" => config.walls[Edge::Left as usize] = parse_override(key, value)?,
+        "wall_right" => config.walls[Edge::Right as usize] = parse_override(key, value)?,
+        "wall_bottom" => config.walls[Edge::Bottom as usize] = parse_override(key, value)?,
+        "wall_top" => config.walls[Edge::Top as usize] = parse_override(key, value)?,
+        "link_speeds" => set!(link_speeds),
+        "speed_x" => set!(speed_x, 10.0..=200.0),
+        "speed_y" => set!(speed_y, 10.0..=200.0),
+        "start_position" => set!(start_position),
+        "msaa_samples" => set!(msaa_samples, 1..=8),
+        "background_image_path" => config.background_image_path = value.to_string(),
+        "background_fit" => set!(background_fit),
+        "show_bounce_counter" => set!(show_bounce_counter),
+        "milestone_effects" => set!(milestone_effects),
+        "seed" => config.seed = parse_optional_override(key, value)?,
+        "easing" => set!(easing),
+        "easing_strength" => set!(easing_strength, 0.1..=1.0),
+        "color_mode" => set!(color_mode),
+        "max_runtime_secs" => config.max_runtime_secs = parse_optional_override(key, value)?,
+        "outline" => set!(outline),
+        "outline_color" => {
+            config.outline_color = parse_hex_color(value)
+                .ok_or_else(|| format!("Invalid value '{}' for {}", value, key))?
+        }
+        "outline_thickness" => set!(outline_thickness, 1.0..=10.0),
+        "flip_on_bounce" => set!(flip_on_bounce),
+        "watch_folder" => set!(watch_folder),
+        "image_cycle" => set!(image_cycle),
+        "image_cycle_secs" => set!(image_cycle_secs, 1.0..=300.0),
+        "neon_glow" => set!(neon_glow),
+        "neon_glow_radius" => set!(neon_glow_radius, 2.0..=40.0),
+        "neon_glow_follows_logo" => set!(neon_glow_follows_logo),
+        "neon_glow_color" => {
+            config.neon_glow_color = parse_hex_color(value)
+                .ok_or_else(|| format!("Invalid value '{}' for {}", value, key))?
+        }
+        "squash" => set!(squash),
+        "squash_intensity" => set!(squash_intensity, 0.05..=0.6),
+        "night_mode" => set!(night_mode),
+        "night_brightness" => set!(night_brightness, 0.1..=1.0),
+        "night_start_hour" => set!(night_start_hour, 0..=23),
+        "night_end_hour" => set!(night_end_hour, 0..=23),
+        "echoes" => set!(echoes),
+        "echo_count" => set!(echo_count, 1..=10),
+        "echo_fade_secs" => set!(echo_fade_secs, 0.5..=10.0),
+        "freeze_on_corner" => set!(freeze_on_corner),
+        "freeze_secs" => set!(freeze_secs, 0.2..=5.0),
+        "initial_hue" => {
+            config.initial_hue =
+                parse_optional_override(key, value)?.map(|hue: i32| hue.rem_euclid(360))
+        }
+        "recolor_cooldown_secs" => set!(recolor_cooldown_secs, 0.0..=1.0),
+        "mouse_exit_distance" => set!(mouse_exit_distance, 0.0..=500.0),
+        "internal_height" => {
+            config.internal_height =
+                parse_optional_override(key, value)?.map(|height: u32| height.clamp(240, 4320))
+        }
+        "varied_logos" => set!(varied_logos),
+        "tight_collision" => set!(tight_collision),
+        "startup_fade_secs" => set!(startup_fade_secs, 0.0..=10.0),
+        "region_width" => set!(region_width, 0.1..=1.0),
+        "region_height" => set!(region_height, 0.1..=1.0),
+        "region_x" => set!(region_x, -0.45..=0.45),
+        "region_y" => set!(region_y, -0.45..=0.45),
+        "region_border" => set!(region_border),
+        "exit_only_on_escape" => set!(exit_only_on_escape),
+        "spawn_interval_secs" => set!(spawn_interval_secs, 0.0..=300.0),
+        "max_logos" => set!(max_logos, 2..=50),
+        "start_axis" => set!(start_axis),
+        "attract_mode" => set!(attract_mode),
+        "obstacle" => set!(obstacle),
+        "obstacle_width" => set!(obstacle_width, 0.05..=0.8),
+        "obstacle_height" => set!(obstacle_height, 0.05..=0.8),
+        "obstacle_x" => set!(obstacle_x, -0.45..=0.45),
+        "obstacle_y" => set!(obstacle_y, -0.45..=0.45),
+        "exit_animation" => set!(exit_animation),
+        "corner_celebration" => set!(corner_celebration),
+        "pixel_shift" => set!(pixel_shift),
+        "pixel_shift_amount" => set!(pixel_shift_amount, 1.0..=20.0),
+        "pixel_shift_period_secs" => set!(pixel_shift_period_secs, 10.0..=1800.0),
+        "icon_set" => {
+            config.icon_set = value
+                .split('|')
+                .filter(|icon| !icon.is_empty())
+                .map(|icon| parse_override(key, icon))
+                .collect::<Result<_, _>>()?
+        }
+        "gravity_well" => set!(gravity_well),
+        "gravity_well_x" => set!(gravity_well_x, -0.5..=0.5),
+        "gravity_well_y" => set!(gravity_well_y, -0.5..=0.5),
+        "gravity_well_strength" => set!(gravity_well_strength, 0.01..=0.5),
+        "image_cache_mb" => set!(image_cache_mb, 0..=1024),
+        "banner_text" => config.banner_text = value.to_string(),
+        "banner_color" => {
+            config.banner_color = parse_hex_color(value)
+                .ok_or_else(|| format!("Invalid value '{}' for {}", value, key))?
+        }
+        "banner_position" => set!(banner_position),
+        "normalize_speed" => set!(normalize_speed),
+        "photo_frame" => set!(photo_frame),
+        "photo_pan_secs" => set!(photo_pan_secs, 5.0..=120.0),
+        "photo_zoom_min" => set!(photo_zoom_min, 1.0..=3.0),
+        "photo_zoom_max" => set!(photo_zoom_max, 1.0..=3.0),
+        "photo_recolor" => set!(photo_recolor),
+        _ => return Err(format!("Unknown setting '{}'", key)),
+    }
+
+    Ok(())
+}
+
+/// Takes the options that apply to every mode out of `args`, checking any
+/// `--set` overrides against a scratch config.
+fn take_options(args: &mut Vec<String>) -> Result<(), String> {
+    if let Some(path) = take_option_value(args, "--config-file")? {
+        *CONFIG_FILE.lock().unwrap() = Some(PathBuf::from(path));
+    }
+
+    if let Some(profile) = take_option_value(args, "--profile")? {
+        *ACTIVE_PROFILE.lock().unwrap() = Some(profile);
+    }
+
+    let mut overrides = Vec::new();
+    while let Some(setting) = take_option_value(args, "--set")? {
+        let (key, value) = setting
+            .split_once('=')
+            .ok_or_else(|| format!("Invalid --set '{}', expected key=value", setting))?;
+        overrides.push((key.trim().to_string(), value.trim().to_string()));
+    }
+    let mut scratch = ScreenSaverConfig::default();
+    for (key, value) in &overrides {
+        apply_override(&mut scratch, key, value)?;
+    }
+    *CONFIG_OVERRIDES.lock().unwrap() = overrides;
+
+    if let Some(path) = take_option_value(args, "--stats-json")? {
+        *STATS_JSON_PATH.lock().unwrap() = Some(path);
+    }
+    Ok(())
+}
+
+/// Logs a bad command line and shows it in a dialog too, as release builds on
+/// Windows have no console to print it to.
+fn exit_with_argument_error(error: &str) -> ! {
+    log_message!("{}", error);
+    MessageDialog::new()
+        .set_level(MessageLevel::Error)
+        .set_title("DVD Screensaver")
+        .set_description(error)
+        .set_buttons(MessageButtons::Ok)
+        .show();
+    std::process::exit(2);
+}
+
+fn take_option_value(args: &mut Vec<String>, name: &str) -> Result<Option<String>, String> {
+    let Some(index) = args.iter().position(|arg| arg == name) else {
+        return Ok(None);
+    };
+    if index + 1 >= args.len() {
+        return Err(format!("Missing value for {}", name));
+    }
+
+    let value = args.remove(index + 1);
+    args.remove(index);
+    Ok(Some(value))
+}
+
+/// Stays resident and launches the fullscreen screensaver (`/s`) once the
+/// system has been idle for the configured timeout, for platforms or setups
+/// without a native screensaver subsystem. The child process exits on input
+/// through the usual `window_event` handling.
+fn run_idle_daemon() {
+    let config = load_effective_config();
+    let timeout = std::time::Duration::from_secs(config.idle_timeout_secs.max(1) as u64);
+
+    let exe = match env::current_exe() {
+        Ok(exe) => exe,
+        Err(error) => {
+            log_message!("Unable to locate the screensaver executable: {}", error);
+            return;
+        }
+    };
+
+    if system_idle_time().is_none() {
+        log_message!("Idle time detection is not available on this system");
+        return;
+    }
+
+    loop {
+        thread::sleep(std::time::Duration::from_secs(1));
+
+        let Some(idle) = system_idle_time() else {
+            continue;
+        };
+
+        if idle < timeout {
+            continue;
+        }
+
+        let mut command = std::process::Command::new(&exe);
+        command.arg("/s");
+        if let Some(profile) = active_profile() {
+            command.args(["--profile", &profile]);
+        }
+        if let Some(path) = CONFIG_FILE.lock().unwrap().as_ref() {
+            command.arg("--config-file").arg(path);
+        }
+
+        match command.status() {
+            Ok(_) => {
+                while system_idle_time().is_some_and(|idle| idle >= timeout) {
+                    thread::sleep(std::time::Duration::from_secs(1));
+                }
+            }
+            Err(error) => {
+                log_message!("Unable to launch the screensaver: {}", error);
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(windows)]
+fn system_idle_time() -> Option<std::time::Duration> {
+    let mut info = LASTINPUTINFO {
+        cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+        dwTime: 0,
+    };
+
+    unsafe {
+        if GetLastInputInfo(&mut info) == FALSE {
+            return None;
+        }
+
+        let idle_ms = GetTickCount().wrapping_sub(info.dwTime);
+        Some(std::time::Duration::from_millis(idle_ms as u64))
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn system_idle_time() -> Option<std::time::Duration> {
+    let output = std::process::Command::new("ioreg")
+        .args(["-c", "IOHIDSystem", "-d", "4"])
+        .output()
+        .ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let line = stdout
+        .lines()
+        .find(|line| line.contains("\"HIDIdleTime\""))?;
+    let nanos: u64 = line.rsplit('=').next()?.trim().parse().ok()?;
+    Some(std::time::Duration::from_nanos(nanos))
+}
+
+/// Relies on `xprintidle`, which reports the X11 idle time in milliseconds.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn system_idle_time() -> Option<std::time::Duration> {
+    let output = std::process::Command::new("xprintidle").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let millis: u64 = String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse()
+        .ok()?;
+    Some(std::time::Duration::from_millis(millis))
+}
+
+fn parse_preview_hwnd(args: &[String]) -> Option<isize> {
+    if args.len() > 2 {
+        args[2].parse::<isize>().ok()
+    } else if args.len() > 1 {
+        let flag = &args[1];
+        if flag.len() > 2 {
+            flag[2..].parse::<isize>().ok()
+        } else {
+            None
+        }
+    } else {
+        None
+    }
+}
+
+fn run_preview_mode(hwnd: Option<isize>) {
+    if PREVIEW_RUNNING.swap(true, Ordering::SeqCst) {
+        std::process::exit(0);
+    }
+
+    unsafe {
+        PREVIEW_PARENT_HWND = hwnd;
+    }
+
+    if hwnd.is_some() {
+        nannou::app(preview_model_embedded).update(update).run();
+    } else {
+        nannou::app(preview_model_standalone).update(update).run();
+    }
+
+    PREVIEW_RUNNING.store(false, Ordering::SeqCst);
+}
+
+#[cfg(windows)]
+fn preview_model_embedded(app: &App) -> Model {
+    let parent_hwnd = unsafe { PREVIEW_PARENT_HWND };
+
+    let _window_id = build_window(
+        app,
+        app.new_window()
+            .size(200, 150)
+            .title("DVD Screensaver Preview")
+            .event(window_event)
+            .raw_event(raw_model_event)
+            .view(view)
+            .decorations(false)
+            .resizable(false)
+            .msaa_samples(load_effective_config().sample_count()),
+    );
+
+    let mut preview_child = None;
+    let mut preview_size = Vec2::new(200.0, 150.0);
+
+    if let Some(parent_hwnd) = parent_hwnd {
+        let window = app.window(_window_id).unwrap();
+
+        if let RawWindowHandle::Win32(handle) = window.winit_window().raw_window_handle() {
+            let child_hwnd = handle.hwnd as HWND;
+
+            if !child_hwnd.is_null() {
+                unsafe {
+                    SetParent(child_hwnd, parent_hwnd as HWND);
+
+                    let new_style = (WS_CHILD | WS_VISIBLE) as isize;
+                    SetWindowLongPtrW(child_hwnd, GWL_STYLE, new_style);
+                }
+
+                if let Some((width, height)) = sync_preview_size(child_hwnd as isize, parent_hwnd) {
+                    let scale_factor = window.scale_factor();
+                    preview_size =
+                        Vec2::new(width as f32 / scale_factor, height as f32 / scale_factor);
+                }
+                preview_child = Some(child_hwnd as isize);
+                log_message!(
+                    "Embedded preview in parent window {:#x} at {}x{}",
+                    parent_hwnd,
+                    preview_size.x,
+                    preview_size.y
+                );
+            }
+        }
+
+        if preview_child.is_none() {
+            log_message!(
+                "Unable to embed preview in parent window {:#x}",
+                parent_hwnd
+            );
+        }
+    }
+
+    let mut model = create_preview_model(app, preview_size, true, parent_hwnd);
+    model.preview_child = preview_child;
+    model
+}
+
+/// Resizes the embedded preview window to fill its parent's client area if
+/// the two differ, e.g. after the settings dialog resized the preview pane.
+/// Returns the parent's client size in pixels.
+#[cfg(windows)]
+fn sync_preview_size(child_hwnd: isize, parent_hwnd: isize) -> Option<(i32, i32)> {
+    unsafe {
+        let mut parent_rect = std::mem::zeroed();
+        let mut child_rect = std::mem::zeroed();
+        if GetClientRect(parent_hwnd as HWND, &mut parent_rect) == FALSE
+            || GetClientRect(child_hwnd as HWND, &mut child_rect) == FALSE
+        {
+            return None;
+        }
+
+        let width = parent_rect.right - parent_rect.left;
+        let height = parent_rect.bottom - parent_rect.top;
+
+        if width != child_rect.right - child_rect.left
+            || height != child_rect.bottom - child_rect.top
+        {
+            MoveWindow(child_hwnd as HWND, 0, 0, width, height, 1);
+        }
+
+        Some((width, height))
+    }
+}
+
+#[cfg(not(windows))]
+fn preview_model_embedded(app: &App) -> Model {
+    preview_model_standalone(app)
+}
+
+fn preview_model_standalone(app: &App) -> Model {
+    let _window_id = build_window(
+        app,
+        app.new_window()
+            .size(200, 150)
+            .title("DVD Screensaver Preview")
+            .event(window_event)
+            .raw_event(raw_model_event)
+            .view(view)
+            .decorations(true)
+            .always_on_top(true)
+            .resizable(false)
+            .msaa_samples(load_effective_config().sample_count()),
+    );
+
+    create_preview_model(app, Vec2::new(200.0, 150.0), true, None)
+}
+
+/// Builds a model for a preview window of `preview_size` points that is a
+/// faithful miniature of the fullscreen screensaver: the logo's size, speed
+/// and shadow offset are all multiplied by the ratio of the preview's width to
+/// the primary monitor's width.
+fn create_preview_model(
+    app: &App,
+    preview_size: Vec2,
+    is_preview: bool,
+    parent_hwnd: Option<isize>,
+) -> Model {
+    let sources = ModelSources::load();
+    let config = &sources.config;
+
+    let preview_scale = preview_size.x / primary_screen_size(app).x;
+
+    let (target_width, target_height) = config.icon_target_size(preview_size, preview_scale);
+
+    let mut icon = config.icon_source();
+    let loaded_image = match get_image_data(&icon, (target_width, target_height)) {
+        Ok(img) => img,
+        Err(error) => {
+            log_message!("Preview icon loading failed: {}, using default icon", error);
+            icon = IconSource::default();
+
+            let data = include_bytes!("../assets/dvd_logo.png");
+            image::load_from_memory(data).expect("Unable to load default icon")
+        }
+    };
+
+    let background = load_background(app, config, preview_size);
+
+    Model::new(
+        sources,
+        (icon, loaded_image),
+        ModelView {
+            win: Rect::from_w_h(preview_size.x, preview_size.y),
+            icon_target: (target_width.max(1), target_height.max(1)),
+            scale: preview_scale,
+            background,
+            is_preview,
+            windowed: false,
+            preview_parent: parent_hwnd,
+        },
+    )
+}
+
+fn show_configuration_dialog() {
+    nannou::app(config_model)
+        .update(config_update)
+        .view(config_view)
+        .run();
+}
+
+fn config_model(app: &App) -> ConfigModel {
+    let saved_geometry =
+        load_window_geometry().filter(|geometry| is_geometry_visible(app, geometry));
+
+    let mut window_builder = app
+        .new_window()
+        .title("DVD Screensaver Configuration")
+        .view(config_view)
+        .raw_event(raw_window_event);
+
+    window_builder = match saved_geometry {
+        Some(geometry) => window_builder.size_pixels(geometry.width, geometry.height),
+        None => window_builder.size(500, 400),
+    };
+
+    let _window_id = build_window(app, window_builder);
+
+    let window = app.window(_window_id).unwrap();
+    if let Some(geometry) = saved_geometry {
+        window.set_outer_position_pixels(geometry.x, geometry.y);
+    }
+
+    let (x, y) = window.outer_position_pixels().unwrap_or((0, 0));
+    let (width, height) = window.inner_size_pixels();
+    let window_geometry = WindowGeometry {
+        x,
+        y,
+        width,
+        height,
+    };
+
+    let egui = Egui::from_window(&window);
+    egui.ctx().set_pixels_per_point(window.scale_factor());
+    configure_fonts(egui.ctx());
+
+    let profile = active_profile().unwrap_or_else(|| DEFAULT_PROFILE.to_string());
+    let config = load_config(Some(&profile));
+    let image_names = BUILT_IN_ICON_NAMES
+        .iter()
+        .chain(&["Custom Icon", "Icon Set"])
+        .map(|name| name.to_string())
+        .collect();
+    let built_in_icons = (0..BUILT_IN_ICON_COUNT)
+        .filter_map(|index| get_image_data(&IconSource::BuiltIn(index), (0, 0)).ok())
+        .enumerate()
+        .map(|(index, image)| {
+            egui.ctx().load_texture(
+                format!("built_in_icon_{}", index),
+                egui_image(&image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)),
+                egui::TextureOptions::default(),
+            )
+        })
+        .collect();
+
+    ConfigModel {
+        egui,
+        config: config.clone(),
+        profile,
+        profile_names: list_profiles(),
+        new_profile_name: String::new(),
+        image_names,
+        custom_image_path: config.custom_image_path,
+        file_dialog_receiver: None,
+        file_dialog_target: ImageTarget::Icon,
+        is_file_dialog_open: false,
+        validated_path: String::new(),
+        validation_receiver: None,
+        validation_result: None,
+        pending_thumbnail: None,
+        thumbnail: None,
+        icon_sizes: Vec::new(),
+        built_in_icons,
+        previewed_background: String::new(),
+        background_receiver: None,
+        background_thumbnail: None,
+        settings_transfer_receiver: None,
+        window_geometry,
+        status_message: None,
+        stats: load_stats(),
+        test_run: None,
+        should_exit: false,
+    }
+}
+
+/// System fonts added after the bundled font, for any glyphs it lacks.
+const FALLBACK_FONT_PATHS: &[&str] = &[
+    "C:/Windows/Fonts/segoeui.ttf",
+    "/System/Library/Fonts/Helvetica.ttc",
+    "/usr/share/fonts/truetype/noto/NotoSans-Regular.ttf",
+    "/usr/share/fonts/noto/NotoSans-Regular.ttf",
+];
+
+/// Uses the bundled DejaVu Sans so the dialog looks the same everywhere,
+/// followed by the first system font found. Fonts are rasterized at the egui
+/// context's pixels-per-point, so this only needs to happen once.
+fn configure_fonts(ctx: &egui::Context) {
+    let mut fonts = egui::FontDefinitions::default();
+    let mut names = vec!["DejaVu Sans".to_owned()];
+    fonts.font_data.insert(
+        names[0].clone(),
+        egui::FontData::from_static(include_bytes!("../assets/fonts/DejaVuSans.ttf")),
+    );
+
+    if let Some((path, font_data)) = FALLBACK_FONT_PATHS
+        .iter()
+        .find_map(|path| std::fs::read(path).ok().map(|data| (path, data)))
+    {
+        names.push(path.to_string());
+        fonts
+            .font_data
+            .insert(path.to_string(), egui::FontData::from_owned(font_data));
+    }
+
+    let proportional = fonts
+        .families
+        .entry(egui::FontFamily::Proportional)
+        .or_default();
+    for (i, name) in names.iter().enumerate() {
+        proportional.insert(i, name.clone());
+    }
+    fonts
+        .families
+        .entry(egui::FontFamily::Monospace)
+        .or_default()
+        .extend(names);
+
+    ctx.set_fonts(fonts);
+}
+
+fn config_update(app: &App, model: &mut ConfigModel, update: Update) {
+    if model.should_exit {
+        save_window_geometry(&model.window_geometry);
+        std::process::exit(0);
+    }
+
+    validate_custom_image(model);
+    load_background_preview(model);
+
+    let egui = &mut model.egui;
+    egui.set_elapsed_time(update.since_start);
+
+    // Follow the window's DPI scaling, which changes when it's dragged to a
+    // monitor with a different scale factor.
+    let scale_factor = app.main_window().scale_factor();
+    if egui.ctx().pixels_per_point() != scale_factor {
+        egui.ctx().set_pixels_per_point(scale_factor);
+    }
+
+    if let Some(receiver) = &model.file_dialog_receiver {
+        if let Ok(result) = receiver.try_recv() {
+            model.is_file_dialog_open = false;
+            if let Some(path) = result {
+                match model.file_dialog_target {
+                    ImageTarget::Icon => {
+                        model.custom_image_path = path;
+                        model.config.custom_image_path = model.custom_image_path.clone();
+                    }
+                    ImageTarget::IconSet => {
+                        model.config.icon_set.push(IconSource::Custom(path.into()));
+                    }
+                    ImageTarget::Background => model.config.background_image_path = path,
+                }
+            }
+            model.file_dialog_receiver = None;
+        }
+    }
+
+    if let Some(receiver) = &model.settings_transfer_receiver {
+        if let Ok(result) = receiver.try_recv() {
+            match result {
+                SettingsTransfer::Exported(path) => {
+                    model.status_message = Some(format!("Exported settings to {}", path.display()));
+                }
+                SettingsTransfer::Imported(config) => {
+                    model.config = *config;
+                    model.custom_image_path = model.config.custom_image_path.clone();
+                    model.status_message =
+                        Some(match save_config(&model.config, Some(&model.profile)) {
+                            Ok(()) => "Imported and saved settings".to_string(),
+                            Err(error) => error,
+                        });
+                }
+                SettingsTransfer::Failed(error) => model.status_message = Some(error),
+                SettingsTransfer::Cancelled => (),
+            }
+            model.settings_transfer_receiver = None;
+        }
+    }
+
+    let ctx = egui.begin_frame();
+
+    if let Some(image) = model.pending_thumbnail.take() {
+        model.thumbnail = Some(ctx.load_texture(
+            "custom_icon_thumbnail",
+            image,
+            egui::TextureOptions::default(),
+        ));
+    }
+
+    if let Some(child) = &mut model.test_run {
+        if !matches!(child.try_wait(), Ok(None)) {
+            model.test_run = None;
+            // The test run counts towards the statistics too.
+            model.stats = load_stats();
+        }
+    }
+
+    // Focus is still from the previous frame here, so pressing Enter to
+    // commit a text field doesn't also save and close the dialog. Keys are
+    // also ignored during a test run so the key that ends it isn't taken as
+    // a shortcut here too.
+    let (save_shortcut, cancel_shortcut) = if ctx.wants_keyboard_input() || model.test_run.is_some()
+    {
+        (false, false)
+    } else {
+        ctx.input(|input| {
+            (
+                input.key_pressed(egui::Key::Enter),
+                input.key_pressed(egui::Key::Escape),
+            )
+        })
+    };
+
+    let mut window_open = true;
+    egui::Window::new("DVD Screensaver Settings")
+        .default_size([450.0, 350.0])
+        .vscroll(true)
+        .open(&mut window_open)
+        .show(&ctx, |ui| {
+            ui.heading("Profile");
+            let mut selected_profile = model.profile.clone();
+            egui::ComboBox::from_label("Active Profile")
+                .selected_text(&selected_profile)
+                .show_ui(ui, |ui| {
+                    for name in &model.profile_names {
+                        ui.selectable_value(&mut selected_profile, name.clone(), name);
+                    }
+                });
+
+            if selected_profile != model.profile {
+                model.config = load_config(Some(&selected_profile));
+                model.custom_image_path = model.config.custom_image_path.clone();
+                model.profile = selected_profile;
+            }
+
+            ui.horizontal(|ui| {
+                ui.text_edit_singleline(&mut model.new_profile_name);
+
+                let name = model.new_profile_name.trim().to_string();
+                if ui
+                    .add_enabled(
+                        is_valid_profile_name(&name),
+                        egui::Button::new("New Profile"),
+                    )
+                    .clicked()
+                {
+                    match save_config(&model.config, Some(&name)) {
+                        Ok(()) => {
+                            model.profile_names = list_profiles();
+                            model.profile = name;
+                            model.new_profile_name.clear();
+                        }
+                        Err(error) => model.status_message = Some(error),
+                    }
+                }
+            });
+
+            ui.separator();
+
+            ui.heading("Movement Speed");
+            if ui
+                .checkbox(&mut model.config.link_speeds, "Link X/Y")
+                .changed()
+                && !model.config.link_speeds
+            {
+                model.config.speed_x = model.config.speed;
+                model.config.speed_y = model.config.speed;
+            }
+            if model.config.link_speeds {
+                ui.add(
+                    egui::Slider::new(&mut model.config.speed, 10.0..=200.0)
+                        .logarithmic(true)
+                        .text("pixels/second"),
+                );
+            } else {
+                ui.add(
+                    egui::Slider::new(&mut model.config.speed_x, 10.0..=200.0)
+                        .logarithmic(true)
+                        .text("horizontal (pixels/second)"),
+                );
+                ui.add(
+                    egui::Slider::new(&mut model.config.speed_y, 10.0..=200.0)
+                        .logarithmic(true)
+                        .text("vertical (pixels/second)"),
+                );
+            }
+            ui.checkbox(
+                &mut model.config.normalize_speed,
+                "Update at the monitor's refresh rate",
+            );
+            ui.checkbox(
+                &mut model.config.photo_frame,
+                "Photo frame: fill the screen and slowly pan and zoom instead",
+            );
+            if model.config.photo_frame {
+                ui.add(
+                    egui::Slider::new(&mut model.config.photo_pan_secs, 5.0..=120.0)
+                        .text("seconds per pan"),
+                );
+                ui.add(
+                    egui::Slider::new(&mut model.config.photo_zoom_min, 1.0..=3.0)
+                        .text("least zoom"),
+                );
+                ui.add(
+                    egui::Slider::new(&mut model.config.photo_zoom_max, 1.0..=3.0)
+                        .text("most zoom"),
+                );
+                model.config.photo_zoom_max =
+                    model.config.photo_zoom_max.max(model.config.photo_zoom_min);
+                ui.checkbox(
+                    &mut model.config.photo_recolor,
+                    "Recolor the image with each pan",
+                );
+            }
+
+            ui.separator();
+
+            ui.heading("Icon Selection");
+            egui::ComboBox::from_label("Select Icon")
+                .selected_text(
+                    &model.image_names[model.config.image_index.min(model.image_names.len() - 1)],
+                )
+                .show_ui(ui, |ui| {
+                    for (i, name) in model.image_names.iter().enumerate() {
+                        ui.selectable_value(&mut model.config.image_index, i, name);
+                    }
+                });
+            ui.checkbox(
+                &mut model.config.randomize_on_launch,
+                "Randomize icon and color on launch",
+            );
+
+            if model.config.image_index == BUILT_IN_ICON_COUNT {
+                ui.label("Custom icon path:");
+                ui.text_edit_singleline(&mut model.custom_image_path);
+
+                ui.horizontal(|ui| {
+                    let button_text = if model.is_file_dialog_open {
+                        "File dialog is open..."
+                    } else {
+                        "Browse File"
+                    };
+
+                    if ui
+                        .add_enabled(!model.is_file_dialog_open, egui::Button::new(button_text))
+                        .clicked()
+                    {
+                        model.file_dialog_receiver = Some(pick_image_file("Select Icon File"));
+                        model.file_dialog_target = ImageTarget::Icon;
+                        model.is_file_dialog_open = true;
+                    }
+
+                    if ui.button("Paste from Clipboard").clicked() {
+                        match save_clipboard_image() {
+                            Ok(path) => {
+                                model.custom_image_path = path.to_string_lossy().to_string();
+                                model.status_message =
+                                    Some("Pasted image from clipboard".to_string());
+                            }
+                            Err(error) => model.status_message = Some(error),
+                        }
+                    }
+
+                    ui.label(format!(
+                        "Supported formats: {}",
+                        SUPPORTED_FORMAT_NAMES.join(", ")
+                    ));
+                });
+
+                ui.checkbox(
+                    &mut model.config.watch_folder,
+                    "If the path is a folder, show its newest image",
+                );
+
+                image_cycle_controls(ui, &mut model.config, "Switch to the folder's next image");
+
+                if !model.custom_image_path.is_empty() {
+                    if Path::new(&model.custom_image_path).is_dir() {
+                        ui.label("Images will be picked from this folder");
+                    } else if !Path::new(&model.custom_image_path).is_file()
+                        && !is_data_uri(&model.custom_image_path)
+                    {
+                        ui.colored_label(egui::Color32::RED, "✗ File does not exist");
+                    } else {
+                        match &model.validation_result {
+                            None => {
+                                ui.label("Checking image...");
+                            }
+                            Some(Ok((width, height))) => {
+                                ui.colored_label(
+                                    egui::Color32::GREEN,
+                                    format!("✓ Image decoded successfully ({}×{})", width, height),
+                                );
+                                if model.icon_sizes.len() > 1 {
+                                    let sizes: Vec<String> = model
+                                        .icon_sizes
+                                        .iter()
+                                        .map(|(width, height)| format!("{}×{}", width, height))
+                                        .collect();
+                                    ui.label(format!(
+                                        "Contains {}; the closest to the logo's size is used",
+                                        sizes.join(", ")
+                                    ));
+                                }
+                                if let Some(thumbnail) = &model.thumbnail {
+                                    ui.image(thumbnail);
+                                }
+                            }
+                            Some(Err(error)) => {
+                                ui.colored_label(
+                                    egui::Color32::RED,
+                                    format!("✗ Unable to decode image: {}", error),
+                                );
+                            }
+                        }
+                    }
+                }
+
+                model.config.custom_image_path = model.custom_image_path.clone();
+
+                ui.separator();
+                ui.label("Quick select:");
+                ui.horizontal(|ui| {
+                    if ui.small_button("Desktop").clicked() {
+                        if let Some(desktop) = dirs::desktop_dir() {
+                            model.custom_image_path = desktop.to_string_lossy().to_string();
+                        }
+                    }
+                    if ui.small_button("Pictures").clicked() {
+                        if let Some(pictures) = dirs::picture_dir() {
+                            model.custom_image_path = pictures.to_string_lossy().to_string();
+                        }
+                    }
+                    if ui.small_button("Downloads").clicked() {
+                        if let Some(downloads) = dirs::download_dir() {
+                            model.custom_image_path = downloads.to_string_lossy().to_string();
+                        }
+                    }
+                });
+            }
+
+            if model.config.image_index == ICON_SET_INDEX {
+                ui.label("Icons, in order:");
+                if model.config.icon_set.is_empty() {
+                    ui.label("The set is empty, so the first built-in icon is shown");
+                }
+
+                let mut swap = None;
+                let mut remove = None;
+                let count = model.config.icon_set.len();
+                for (i, icon) in model.config.icon_set.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("{}. {}", i + 1, icon.label()));
+                        if ui
+                            .add_enabled(i > 0, egui::Button::new("Up").small())
+                            .clicked()
+                        {
+                            swap = Some((i - 1, i));
+                        }
+                        if ui
+                            .add_enabled(i + 1 < count, egui::Button::new("Down").small())
+                            .clicked()
+                        {
+                            swap = Some((i, i + 1));
+                        }
+                        if ui.small_button("Remove").clicked() {
+                            remove = Some(i);
+                        }
+                    });
+                }
+                if let Some((a, b)) = swap {
+                    model.config.icon_set.swap(a, b);
+                }
+                if let Some(i) = remove {
+                    model.config.icon_set.remove(i);
+                }
+
+                ui.horizontal(|ui| {
+                    for (index, name) in BUILT_IN_ICON_NAMES.iter().enumerate() {
+                        if ui.button(format!("Add {}", name)).clicked() {
+                            model.config.icon_set.push(IconSource::BuiltIn(index));
+                        }
+                    }
+                    if ui
+                        .add_enabled(
+                            !model.is_file_dialog_open,
+                            egui::Button::new("Add Image File..."),
+                        )
+                        .clicked()
+                    {
+                        model.file_dialog_receiver = Some(pick_image_file("Add Icon to Set"));
+                        model.file_dialog_target = ImageTarget::IconSet;
+                        model.is_file_dialog_open = true;
+                    }
+                });
+
+                image_cycle_controls(ui, &mut model.config, "Switch to the set's next image");
+            }
+
+            ui.separator();
+
+            ui.heading("Icon Size");
+            ui.horizontal(|ui| {
+                let is_fraction = model.config.size_mode == SizeMode::Fraction;
+                if ui.radio(is_fraction, "Fraction of screen").clicked() {
+                    model.config.size_mode = SizeMode::Fraction;
+                }
+                if ui.radio(!is_fraction, "Fixed size").clicked() && is_fraction {
+                    model.config.size_mode = SizeMode::Pixels(SizeMode::DEFAULT_PIXELS);
+                }
+            });
+            match &mut model.config.size_mode {
+                SizeMode::Fraction => {
+                    ui.add(
+                        egui::Slider::new(&mut model.config.size_factor, 0.05..=0.5)
+                            .text("size multiplier"),
+                    );
+                }
+                SizeMode::Pixels(pixels) => {
+                    ui.add(
+                        egui::Slider::new(pixels, 16..=1024)
+                            .logarithmic(true)
+                            .text("longest edge (px)"),
+                    );
+                }
+            }
+
+            egui::ComboBox::from_label("Anti-aliasing")
+                .selected_text(match model.config.sample_count() {
+                    1 => "Off".to_string(),
+                    samples => format!("{}x MSAA", samples),
+                })
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut model.config.msaa_samples, 1, "Off");
+                    for samples in [2, 4, 8] {
+                        ui.selectable_value(
+                            &mut model.config.msaa_samples,
+                            samples,
+                            format!("{}x MSAA", samples),
+                        );
+                    }
+                });
+
+            egui::ComboBox::from_label("Render resolution")
+                .selected_text(match model.config.internal_height {
+                    None => "Native".to_string(),
+                    Some(height) => format!("{}p, upscaled", height),
+                })
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut model.config.internal_height, None, "Native");
+                    for height in INTERNAL_HEIGHTS {
+                        ui.selectable_value(
+                            &mut model.config.internal_height,
+                            Some(height),
+                            format!("{}p, upscaled", height),
+                        );
+                    }
+                });
+
+            egui::ComboBox::from_label("Scaling")
+                .selected_text(model.config.scale_filter.label())
+                .show_ui(ui, |ui| {
+                    for filter in ScaleFilter::ALL {
+                        ui.selectable_value(&mut model.config.scale_filter, filter, filter.label());
+                    }
+                });
+
+            ui.separator();
+
+            ui.heading("Logos");
+            let ramping = model.config.spawn_interval_secs > 0.0;
+            ui.add_enabled(
+                !ramping,
+                egui::Slider::new(&mut model.config.logo_count, 1..=20).text("logo count"),
+            );
+            ui.add(
+                egui::Slider::new(&mut model.config.spawn_interval_secs, 0.0..=300.0)
+                    .text("add a logo every (s, 0 = off)"),
+            );
+            ui.add_enabled(
+                ramping,
+                egui::Slider::new(&mut model.config.max_logos, 2..=50).text("up to logos"),
+            );
+            ui.add_enabled(
+                model.config.max_logo_count() > 1,
+                egui::Checkbox::new(&mut model.config.varied_logos, "Different image per logo"),
+            )
+            .on_hover_text("Built-in icons, or the images in the custom icon's folder");
+            ui.checkbox(
+                &mut model.config.tight_collision,
+                "Bounce on visible pixels, ignoring transparent padding",
+            );
+            egui::ComboBox::from_label("Start position")
+                .selected_text(model.config.start_position.label())
+                .show_ui(ui, |ui| {
+                    for position in StartPosition::ALL {
+                        ui.selectable_value(
+                            &mut model.config.start_position,
+                            position,
+                            position.label(),
+                        );
+                    }
+                });
+            egui::ComboBox::from_label("Start moving")
+                .selected_text(model.config.start_axis.label())
+                .show_ui(ui, |ui| {
+                    for axis in StartAxis::ALL {
+                        ui.selectable_value(&mut model.config.start_axis, axis, axis.label());
+                    }
+                });
+            ui.add(
+                egui::Slider::new(&mut model.config.speed_variance, 0.0..=50.0)
+                    .text("speed variance (%)"),
+            );
+            ui.add(
+                egui::Slider::new(&mut model.config.wall_margin, 0.0..=200.0)
+                    .text("wall margin (px)"),
+            );
+            ui.collapsing("Bounce region", |ui| {
+                ui.add(
+                    egui::Slider::new(&mut model.config.region_width, 0.1..=1.0)
+                        .text("width (of screen)"),
+                );
+                ui.add(
+                    egui::Slider::new(&mut model.config.region_height, 0.1..=1.0)
+                        .text("height (of screen)"),
+                );
+                ui.add(
+                    egui::Slider::new(&mut model.config.region_x, -0.45..=0.45)
+                        .text("horizontal offset"),
+                );
+                ui.add(
+                    egui::Slider::new(&mut model.config.region_y, -0.45..=0.45)
+                        .text("vertical offset"),
+                );
+                ui.checkbox(&mut model.config.region_border, "Draw the region's border");
+            });
+            ui.checkbox(&mut model.config.obstacle, "Bounce off a block on screen");
+            if model.config.obstacle {
+                ui.add(
+                    egui::Slider::new(&mut model.config.obstacle_width, 0.05..=0.8)
+                        .text("block width (of screen)"),
+                );
+                ui.add(
+                    egui::Slider::new(&mut model.config.obstacle_height, 0.05..=0.8)
+                        .text("block height (of screen)"),
+                );
+                ui.add(
+                    egui::Slider::new(&mut model.config.obstacle_x, -0.45..=0.45)
+                        .text("block horizontal offset"),
+                );
+                ui.add(
+                    egui::Slider::new(&mut model.config.obstacle_y, -0.45..=0.45)
+                        .text("block vertical offset"),
+                );
+            }
+            ui.checkbox(
+                &mut model.config.gravity_well,
+                "Curve paths towards a gravity well",
+            );
+            if model.config.gravity_well {
+                ui.add(
+                    egui::Slider::new(&mut model.config.gravity_well_strength, 0.01..=0.5)
+                        .logarithmic(true)
+                        .text("pull strength"),
+                );
+                ui.add(
+                    egui::Slider::new(&mut model.config.gravity_well_x, -0.5..=0.5)
+                        .text("well horizontal offset"),
+                );
+                ui.add(
+                    egui::Slider::new(&mut model.config.gravity_well_y, -0.5..=0.5)
+                        .text("well vertical offset"),
+                );
+            }
+            ui.checkbox(
+                &mut model.config.logo_collision,
+                "Logos bounce off each other",
+            );
+            ui.checkbox(&mut model.config.easing, "Slow down near walls");
+            if model.config.easing {
+                ui.add(
+                    egui::Slider::new(&mut model.config.easing_strength, 0.1..=1.0)
+                        .text("easing strength"),
+                );
+            }
+
+            ui.label("Walls:");
+            egui::Grid::new("walls").show(ui, |ui| {
+                for edge in Edge::ALL {
+                    let wall = &mut model.config.walls[edge as usize];
+                    ui.label(edge.label());
+                    egui::ComboBox::from_id_source(edge.name())
+                        .selected_text(wall.label())
+                        .show_ui(ui, |ui| {
+                            for mode in WallMode::ALL {
+                                ui.selectable_value(wall, mode, mode.label());
+                            }
+                        });
+                    ui.end_row();
+                }
+            });
+
+            ui.separator();
+
+            ui.heading("Behavior");
+            ui.checkbox(&mut model.config.hide_cursor, "Hide mouse cursor");
+            ui.checkbox(
+                &mut model.config.exit_only_on_escape,
+                "Exit only on Escape, ignoring other input",
+            );
+            ui.checkbox(
+                &mut model.config.exit_animation,
+                "Shrink and fade out when exiting",
+            )
+            .on_hover_text("Any further input exits at once");
+            ui.add_enabled(
+                !model.config.exit_only_on_escape,
+                egui::Slider::new(&mut model.config.mouse_exit_distance, 0.0..=500.0)
+                    .text("mouse movement to exit (px)"),
+            );
+            ui.add(
+                egui::Slider::new(&mut model.config.idle_timeout_secs, 10..=3600)
+                    .logarithmic(true)
+                    .text("idle timeout for --daemon (seconds)"),
+            );
+            ui.horizontal(|ui| {
+                let mut seeded = model.config.seed.is_some();
+                if ui
+                    .checkbox(&mut seeded, "Same colors every run, seed:")
+                    .changed()
+                {
+                    model.config.seed = seeded.then_some(0);
+                }
+                if let Some(seed) = &mut model.config.seed {
+                    ui.add(egui::DragValue::new(seed));
+                }
+            });
+            ui.horizontal(|ui| {
+                let mut fixed = model.config.initial_hue.is_some();
+                if ui.checkbox(&mut fixed, "Fixed first color, hue:").changed() {
+                    model.config.initial_hue = fixed.then_some(0);
+                }
+                if let Some(hue) = &mut model.config.initial_hue {
+                    ui.add(egui::DragValue::new(hue).clamp_range(0..=359).suffix("°"));
+                }
+            });
+            ui.horizontal(|ui| {
+                let mut limited = model.config.max_runtime_secs.is_some();
+                if ui
+                    .checkbox(&mut limited, "Exit automatically after")
+                    .changed()
+                {
+                    model.config.max_runtime_secs = limited.then_some(3600);
+                }
+                if let Some(secs) = &mut model.config.max_runtime_secs {
+                    ui.add(
+                        egui::DragValue::new(secs)
+                            .clamp_range(1..=604_800)
+                            .suffix(" s"),
+                    );
+                }
+            });
+
+            ui.separator();
+
+            ui.heading("Background");
+            ui.label("Background image (leave empty for black):");
+            ui.horizontal(|ui| {
+                ui.text_edit_singleline(&mut model.config.background_image_path);
+
+                if ui
+                    .add_enabled(!model.is_file_dialog_open, egui::Button::new("Browse"))
+                    .clicked()
+                {
+                    model.file_dialog_receiver = Some(pick_image_file("Select Background Image"));
+                    model.file_dialog_target = ImageTarget::Background;
+                    model.is_file_dialog_open = true;
+                }
+
+                if ui.button("Clear").clicked() {
+                    model.config.background_image_path.clear();
+                }
+            });
+
+            if !model.config.background_image_path.is_empty() {
+                let path = Path::new(&model.config.background_image_path);
+                if !path.is_file() {
+                    ui.colored_label(egui::Color32::RED, "✗ File does not exist");
+                } else if !is_supported_image(path) {
+                    ui.colored_label(
+                        egui::Color32::from_rgb(255, 165, 0),
+                        "⚠ Format may not be supported",
+                    );
+                }
+            }
+
+            egui::ComboBox::from_label("Fit")
+                .selected_text(model.config.background_fit.label())
+                .show_ui(ui, |ui| {
+                    for fit in BackgroundFit::ALL {
+                        ui.selectable_value(&mut model.config.background_fit, fit, fit.label());
+                    }
+                });
+
+            let logo = match model.config.icon_source() {
+                IconSource::BuiltIn(index) => model.built_in_icons.get(index),
+                IconSource::Custom(_) if model.config.image_index == BUILT_IN_ICON_COUNT => {
+                    model.thumbnail.as_ref()
+                }
+                IconSource::Custom(_) => None,
+            };
+            paint_background_preview(ui, &model.config, model.background_thumbnail.as_ref(), logo);
+
+            ui.separator();
+
+            ui.heading("Effects");
+            ui.horizontal_wrapped(|ui| {
+                let is_tint = matches!(model.config.color_mode, ColorMode::Tint { .. });
+                let is_rainbow = matches!(model.config.color_mode, ColorMode::RainbowCycle { .. });
+                let is_pulse = matches!(model.config.color_mode, ColorMode::BrightnessPulse { .. });
+                if ui
+                    .radio(model.config.color_mode == ColorMode::Rotate, "Rotate hue")
+                    .clicked()
+                {
+                    model.config.color_mode = ColorMode::Rotate;
+                }
+                if ui.radio(is_tint, "Tint toward a color").clicked() && !is_tint {
+                    model.config.color_mode = ColorMode::DEFAULT_TINT;
+                }
+                if ui.radio(is_rainbow, "Cycle through the rainbow").clicked() && !is_rainbow {
+                    model.config.color_mode = ColorMode::DEFAULT_RAINBOW;
+                }
+                if ui
+                    .radio(is_pulse, "Keep the color, flash on bounce")
+                    .clicked()
+                    && !is_pulse
+                {
+                    model.config.color_mode = ColorMode::DEFAULT_PULSE;
+                }
+            });
+            if let ColorMode::Tint { base, variation } = &mut model.config.color_mode {
+                ui.horizontal(|ui| {
+                    ui.label("Base color:");
+                    ui.color_edit_button_srgb(base);
+                });
+                ui.add(egui::Slider::new(variation, 0.0..=180.0).text("hue variation (°)"));
+            }
+            if let ColorMode::RainbowCycle { speed } = &mut model.config.color_mode {
+                ui.add(egui::Slider::new(speed, 5.0..=360.0).text("cycle speed (°/s)"));
+            }
+            if let ColorMode::BrightnessPulse { intensity } = &mut model.config.color_mode {
+                ui.add(egui::Slider::new(intensity, 0.1..=1.0).text("flash intensity"));
+            }
+            ui.add(
+                egui::Slider::new(&mut model.config.logo_opacity, 0.05..=1.0).text("logo opacity"),
+            );
+            ui.add(egui::Slider::new(&mut model.config.brightness, -100..=100).text("brightness"));
+            ui.add(egui::Slider::new(&mut model.config.contrast, -50.0..=100.0).text("contrast"));
+            ui.add(egui::Slider::new(&mut model.config.saturation, 0.0..=2.0).text("saturation"));
+            ui.checkbox(&mut model.config.invert, "Invert colors");
+            ui.checkbox(
+                &mut model.config.attract_mode,
+                "Strobe colors for a moment at startup",
+            );
+            ui.checkbox(&mut model.config.night_mode, "Dim at night");
+            if model.config.night_mode {
+                ui.add(
+                    egui::Slider::new(&mut model.config.night_brightness, 0.1..=1.0)
+                        .text("night brightness"),
+                );
+                ui.horizontal(|ui| {
+                    ui.label("Night from");
+                    ui.add(
+                        egui::DragValue::new(&mut model.config.night_start_hour)
+                            .clamp_range(0..=23)
+                            .suffix(":00"),
+                    );
+                    ui.label("to");
+                    ui.add(
+                        egui::DragValue::new(&mut model.config.night_end_hour)
+                            .clamp_range(0..=23)
+                            .suffix(":00"),
+                    );
+                });
+            }
+            ui.add(
+                egui::Slider::new(&mut model.config.startup_fade_secs, 0.0..=10.0)
+                    .text("fade in at startup (s)"),
+            );
+            ui.checkbox(
+                &mut model.config.pixel_shift,
+                "Shift pixels slowly to prevent burn-in",
+            );
+            if model.config.pixel_shift {
+                ui.add(
+                    egui::Slider::new(&mut model.config.pixel_shift_amount, 1.0..=20.0)
+                        .text("shift (px)"),
+                );
+                ui.add(
+                    egui::Slider::new(&mut model.config.pixel_shift_period_secs, 10.0..=1800.0)
+                        .logarithmic(true)
+                        .text("drift period (s)"),
+                );
+            }
+            ui.checkbox(&mut model.config.shadow, "Drop shadow");
+            if model.config.shadow {
+                ui.add(
+                    egui::Slider::new(&mut model.config.shadow_offset, 1.0..=30.0)
+                        .text("shadow offset (px)"),
+                );
+                ui.add(
+                    egui::Slider::new(&mut model.config.shadow_opacity, 0.05..=1.0)
+                        .text("shadow opacity"),
+                );
+            }
+
+            ui.checkbox(&mut model.config.outline, "Outline");
+            if model.config.outline {
+                ui.horizontal(|ui| {
+                    ui.label("Outline color:");
+                    ui.color_edit_button_srgb(&mut model.config.outline_color);
+                });
+                ui.add(
+                    egui::Slider::new(&mut model.config.outline_thickness, 1.0..=10.0)
+                        .text("outline thickness (px)"),
+                );
+            }
+
+            ui.checkbox(
+                &mut model.config.flip_on_bounce,
+                "Flip to face direction of travel",
+            );
+
+            ui.checkbox(
+                &mut model.config.echoes,
+                "Leave echoes where the logo bounces",
+            );
+            if model.config.echoes {
+                ui.add(egui::Slider::new(&mut model.config.echo_count, 1..=10).text("echoes"));
+                ui.add(
+                    egui::Slider::new(&mut model.config.echo_fade_secs, 0.5..=10.0)
+                        .text("echo fade (s)"),
+                );
+            }
+
+            ui.add(
+                egui::Slider::new(&mut model.config.recolor_cooldown_secs, 0.0..=1.0)
+                    .text("min. time between color changes (s)"),
+            );
+
+            ui.checkbox(
+                &mut model.config.freeze_on_corner,
+                "Freeze briefly on a corner hit",
+            );
+            if model.config.freeze_on_corner {
+                ui.add(
+                    egui::Slider::new(&mut model.config.freeze_secs, 0.2..=5.0).text("freeze (s)"),
+                );
+            }
+            ui.checkbox(
+                &mut model.config.corner_celebration,
+                "Celebrate corner hits with confetti",
+            );
+
+            ui.checkbox(&mut model.config.squash, "Squash on impact");
+            if model.config.squash {
+                ui.add(
+                    egui::Slider::new(&mut model.config.squash_intensity, 0.05..=0.6)
+                        .text("squash intensity"),
+                );
+            }
+
+            ui.checkbox(
+                &mut model.config.neon_glow,
+                "Neon glow around the logo's shape",
+            );
+            if model.config.neon_glow {
+                ui.add(
+                    egui::Slider::new(&mut model.config.neon_glow_radius, 2.0..=40.0)
+                        .text("neon glow radius (px)"),
+                );
+                ui.checkbox(
+                    &mut model.config.neon_glow_follows_logo,
+                    "Match the logo's color",
+                );
+                if !model.config.neon_glow_follows_logo {
+                    ui.horizontal(|ui| {
+                        ui.label("Neon glow color:");
+                        ui.color_edit_button_srgb(&mut model.config.neon_glow_color);
+                    });
+                }
+            }
+
+            ui.checkbox(&mut model.config.glow, "Glow pulse on color change");
+            if model.config.glow {
+                ui.add(
+                    egui::Slider::new(&mut model.config.glow_intensity, 0.1..=1.0)
+                        .text("glow intensity"),
+                );
+            }
+
+            ui.checkbox(&mut model.config.show_bounce_counter, "Show bounce counter");
+            ui.checkbox(
+                &mut model.config.milestone_effects,
+                format!("Flash every {} bounces", BOUNCE_MILESTONE),
+            );
+
+            ui.label("Banner message (leave empty for none):");
+            ui.text_edit_singleline(&mut model.config.banner_text);
+            if !model.config.banner_text.is_empty() {
+                ui.horizontal(|ui| {
+                    ui.label("Banner color:");
+                    ui.color_edit_button_srgb(&mut model.config.banner_color);
+                    egui::ComboBox::from_label("Banner edge")
+                        .selected_text(model.config.banner_position.label())
+                        .show_ui(ui, |ui| {
+                            for position in BannerPosition::ALL {
+                                ui.selectable_value(
+                                    &mut model.config.banner_position,
+                                    position,
+                                    position.label(),
+                                );
+                            }
+                        });
+                });
+            }
+
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                if ui.button("Save and Exit").clicked() || save_shortcut {
+                    // Stay open on failure so the settings aren't silently lost.
+                    match save_config(&model.config, Some(&model.profile)) {
+                        Ok(()) => {
+                            save_last_profile(&model.profile);
+                            model.should_exit = true;
+                        }
+                        Err(error) => model.status_message = Some(error),
+                    }
+                }
+
+                if ui.button("Cancel").clicked() || cancel_shortcut {
+                    model.should_exit = true;
+                }
+
+                let test_run_text = if model.test_run.is_some() {
+                    "Test Running..."
+                } else {
+                    "Test Run"
+                };
+                if ui
+                    .add_enabled(model.test_run.is_none(), egui::Button::new(test_run_text))
+                    .clicked()
+                {
+                    match save_config(&model.config, Some(&model.profile))
+                        .and_then(|()| start_test_run(&model.profile))
+                    {
+                        Ok(child) => model.test_run = Some(child),
+                        Err(error) => model.status_message = Some(error),
+                    }
+                }
+
+                if ui.button("Reset to Default").clicked() {
+                    model.config = ScreenSaverConfig::default();
+                    model.custom_image_path = String::new();
+                }
+
+                if ui.button("Open Config Folder").clicked() {
+                    if let Err(error) = open_config_folder() {
+                        model.status_message = Some(error);
+                    }
+                }
+            });
+
+            ui.horizontal(|ui| {
+                let idle = model.settings_transfer_receiver.is_none();
+
+                if ui
+                    .add_enabled(idle, egui::Button::new("Export Settings..."))
+                    .clicked()
+                {
+                    let (sender, receiver) = mpsc::channel();
+                    model.settings_transfer_receiver = Some(receiver);
+
+                    let config = model.config.clone();
+                    thread::spawn(move || {
+                        let result = match FileDialog::new()
+                            .add_filter("DVD Screensaver Settings", &[SETTINGS_FILE_EXTENSION])
+                            .set_file_name(format!("settings.{}", SETTINGS_FILE_EXTENSION))
+                            .save_file()
+                        {
+                            Some(path) => match export_settings(&config, &path) {
+                                Ok(()) => SettingsTransfer::Exported(path),
+                                Err(error) => SettingsTransfer::Failed(error),
+                            },
+                            None => SettingsTransfer::Cancelled,
+                        };
+                        let _ = sender.send(result);
+                    });
+                }
+
+                if ui
+                    .add_enabled(idle, egui::Button::new("Import Settings..."))
+                    .clicked()
+                {
+                    let (sender, receiver) = mpsc::channel();
+                    model.settings_transfer_receiver = Some(receiver);
+
+                    thread::spawn(move || {
+                        let result = match FileDialog::new()
+                            .add_filter("DVD Screensaver Settings", &[SETTINGS_FILE_EXTENSION])
+                            .pick_file()
+                        {
+                            Some(path) => match import_settings(&path) {
+                                Ok(config) => SettingsTransfer::Imported(Box::new(config)),
+                                Err(error) => SettingsTransfer::Failed(error),
+                            },
+                            None => SettingsTransfer::Cancelled,
+                        };
+                        let _ = sender.send(result);
+                    });
+                }
+            });
+
+            if let Some(message) = &model.status_message {
+                ui.label(message);
+            }
+
+            ui.separator();
+            ui.label(format!(
+                "Corner hits: {}    Longest without a corner: {}",
+                model.stats.corner_hits,
+                format_duration(model.stats.longest_without_corner_secs)
+            ));
+            ui.small("Tip: Drag image files to the path field to quickly set the path");
+        });
+
+    if !window_open {
+        model.should_exit = true;
+    }
+}
+
+/// Launches the fullscreen screensaver with `profile`, as "Test Run" does.
+fn start_test_run(profile: &str) -> Result<std::process::Child, String> {
+    let exe = env::current_exe().map_err(|e| format!("Unable to locate executable: {}", e))?;
+    let mut command = std::process::Command::new(exe);
+    command.args(["/s", "--profile", profile]);
+    if let Some(path) = CONFIG_FILE.lock().unwrap().as_ref() {
+        command.arg("--config-file").arg(path);
+    }
+    command
+        .spawn()
+        .map_err(|e| format!("Unable to start the screensaver: {}", e))
+}
+
+/// Longest edge, in points, of the custom icon preview in the config dialog.
+const THUMBNAIL_SIZE: u32 = 96;
+
+/// Shows an open dialog for an image file on a background thread; the chosen
+/// path, if any, arrives on the returned channel.
+fn pick_image_file(title: &'static str) -> mpsc::Receiver<Option<String>> {
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        let dialog = FileDialog::new()
+            .add_filter("Image Files", SUPPORTED_EXTENSIONS)
+            .add_filter("PNG Files", &["png"])
+            .add_filter("JPEG Files", &["jpg", "jpeg"])
+            .add_filter("GIF Files", &["gif"])
+            .add_filter("BMP Files", &["bmp"])
+            .add_filter("ICO Files", &["ico"])
+            .add_filter("Cursor Files", &["cur", "ani"])
+            .add_filter("TIFF Files", &["tiff", "tif"])
+            .add_filter("WebP Files", &["webp"])
+            .add_filter("SVG Files", &["svg"]);
+        #[cfg(feature = "jxl")]
+        let dialog = dialog.add_filter("JPEG XL Files", &["jxl"]);
+        #[cfg(feature = "avif")]
+        let dialog = dialog.add_filter("AVIF Files", &["avif"]);
+        let result = dialog
+            .add_filter("All Files", &["*"])
+            .set_title(title)
+            .pick_file();
+
+        let path_string = result.map(|path| path.to_string_lossy().to_string());
+        let _ = sender.send(path_string);
+    });
+
+    receiver
+}
+
+/// Decodes the custom icon on a background thread whenever its path changes,
+/// so the dialog reports whether the screensaver will actually be able to use
+/// it rather than only checking the file extension.
+fn validate_custom_image(model: &mut ConfigModel) {
+    if let Some(receiver) = &model.validation_receiver {
+        if let Ok(result) = receiver.try_recv() {
+            model.validation_result = Some(result.map(|decoded| {
+                model.pending_thumbnail = Some(decoded.thumbnail);
+                model.icon_sizes = decoded.icon_sizes;
+                decoded.dimensions
+            }));
+            model.validation_receiver = None;
+        }
+    }
+
+    if model.validated_path == model.custom_image_path {
+        return;
+    }
+
+    model.validated_path = model.custom_image_path.clone();
+    model.validation_result = None;
+    model.pending_thumbnail = None;
+    model.thumbnail = None;
+    model.icon_sizes.clear();
+    // Dropping the previous receiver discards any result for a stale path.
+    model.validation_receiver = None;
+
+    if !Path::new(&model.validated_path).is_file() && !is_data_uri(&model.validated_path) {
+        return;
+    }
+
+    let (sender, receiver) = mpsc::channel();
+    model.validation_receiver = Some(receiver);
+
+    let path = model.validated_path.clone();
+    thread::spawn(move || {
+        let icon_sizes = match read_icon_file(Path::new(&path)) {
+            Some(Ok(data)) => icon_entries(&data)
+                .map(|entries| {
+                    entries
+                        .iter()
+                        .map(|entry| (entry.width, entry.height))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        };
+        let result = load_image_safe(&path, (THUMBNAIL_SIZE, THUMBNAIL_SIZE))
+            .map(|image| DecodedIcon {
+                dimensions: image.dimensions(),
+                thumbnail: egui_image(&image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)),
+                icon_sizes,
+            })
+            .map_err(|e| e.to_string());
+        let _ = sender.send(result);
+    });
+}
+
+/// Decodes the background image for the preview whenever its path changes.
+fn load_background_preview(model: &mut ConfigModel) {
+    if let Some(receiver) = &model.background_receiver {
+        if let Ok(image) = receiver.try_recv() {
+            model.background_thumbnail = image.map(|image| {
+                model.egui.ctx().load_texture(
+                    "background_preview",
+                    image,
+                    egui::TextureOptions::default(),
+                )
+            });
+            model.background_receiver = None;
+        }
+    }
+
+    if model.previewed_background == model.config.background_image_path {
+        return;
+    }
+
+    model.previewed_background = model.config.background_image_path.clone();
+    model.background_thumbnail = None;
+    model.background_receiver = None;
+
+    if !Path::new(&model.previewed_background).is_file() {
+        return;
+    }
+
+    let (sender, receiver) = mpsc::channel();
+    model.background_receiver = Some(receiver);
+
+    let path = model.previewed_background.clone();
+    let size = (
+        BACKGROUND_PREVIEW_SIZE.x as u32,
+        BACKGROUND_PREVIEW_SIZE.y as u32,
+    );
+    thread::spawn(move || {
+        let image = load_image_safe(&path, size)
+            .ok()
+            .map(|image| egui_image(&image.thumbnail(size.0 * 2, size.1 * 2)));
+        let _ = sender.send(image);
+    });
+}
+
+fn egui_image(image: &DynamicImage) -> egui::ColorImage {
+    let image = image.to_rgba8();
+    let size = [image.width() as usize, image.height() as usize];
+    egui::ColorImage::from_rgba_unmultiplied(size, image.as_raw())
+}
+
+/// The size of the background preview, in points.
+const BACKGROUND_PREVIEW_SIZE: egui::Vec2 = egui::vec2(192.0, 108.0);
+
+/// The screen width the preview stands in for, to size fixed-size logos.
+const PREVIEW_REFERENCE_WIDTH: f32 = 1920.0;
+
+/// When to step through a folder's or the icon set's images.
+fn image_cycle_controls(ui: &mut egui::Ui, config: &mut ScreenSaverConfig, label: &str) {
+    egui::ComboBox::from_label(label)
+        .selected_text(config.image_cycle.label())
+        .show_ui(ui, |ui| {
+            for cycle in ImageCycle::ALL {
+                ui.selectable_value(&mut config.image_cycle, cycle, cycle.label());
+            }
+        });
+    if config.image_cycle == ImageCycle::OnTimer {
+        ui.add(
+            egui::Slider::new(&mut config.image_cycle_secs, 1.0..=300.0).text("seconds per image"),
+        );
+    }
+}
+
+/// A miniature screen with the background fitted as the screensaver fits it
+/// and the logo centered on top, in its original colors.
+fn paint_background_preview(
+    ui: &mut egui::Ui,
+    config: &ScreenSaverConfig,
+    background: Option<&egui::TextureHandle>,
+    logo: Option<&egui::TextureHandle>,
+) {
+    let (rect, _) = ui.allocate_exact_size(BACKGROUND_PREVIEW_SIZE, egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 0.0, egui::Color32::BLACK);
+    let uv = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
+
+    if let Some(background) = background {
+        let size = background.size_vec2();
+        let scale = config.background_fit.scale(
+            Vec2::new(size.x, size.y),
+            Vec2::new(rect.width(), rect.height()),
+        );
+        let background_rect = egui::Rect::from_center_size(rect.center(), size * scale);
+        painter.image(background.id(), background_rect, uv, egui::Color32::WHITE);
+    }
+
+    if let Some(logo) = logo {
+        let area = Vec2::new(rect.width(), rect.height());
+        let (width, height) = config.icon_target_size(area, rect.width() / PREVIEW_REFERENCE_WIDTH);
+        let size = logo.size_vec2();
+        let scale = (width as f32 / size.x).min(height as f32 / size.y);
+        let logo_rect = egui::Rect::from_center_size(rect.center(), size * scale);
+        let alpha = (config.logo_opacity.clamp(0.0, 1.0) * 255.0) as u8;
+        painter.image(
+            logo.id(),
+            logo_rect,
+            uv,
+            egui::Color32::from_white_alpha(alpha),
+        );
+    }
+}
+
+fn config_view(_app: &App, model: &ConfigModel, frame: Frame) {
+    frame.clear(nannou::color::rgb(0.1, 0.1, 0.1));
+    if let Err(error) = model.egui.draw_to_frame(&frame) {
+        log_message!("Unable to draw the settings window: {}", error);
+    }
+}
+
+fn raw_window_event(
+    _app: &App,
+    model: &mut ConfigModel,
+    event: &nannou::winit::event::WindowEvent,
+) {
+    model.egui.handle_raw_event(event);
+
+    match event {
+        nannou::winit::event::WindowEvent::CloseRequested => model.should_exit = true,
+        nannou::winit::event::WindowEvent::Moved(position) => {
+            model.window_geometry.x = position.x;
+            model.window_geometry.y = position.y;
+        }
+        nannou::winit::event::WindowEvent::Resized(size) => {
+            model.window_geometry.width = size.width;
+            model.window_geometry.height = size.height;
+        }
+        _ => (),
+    }
+}
+
+fn open_config_folder() -> Result<(), String> {
+    let config_path = get_config_path();
+    let folder = config_path.parent().unwrap_or(Path::new("."));
+    let _ = create_dir_all(folder);
+
+    let program = if cfg!(windows) {
+        "explorer"
+    } else if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+
+    std::process::Command::new(program)
+        .arg(folder)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Unable to open {}: {}", folder.display(), e))
+}
+
+fn get_ui_state_path() -> PathBuf {
+    get_config_path()
+        .parent()
+        .map(|dir| dir.join("ui_state.ini"))
+        .unwrap_or_else(|| PathBuf::from("ui_state.ini"))
+}
+
+fn load_window_geometry() -> Option<WindowGeometry> {
+    let contents = std::fs::read_to_string(get_ui_state_path()).ok()?;
+    let mut lines = contents.lines();
+
+    let geometry = WindowGeometry {
+        x: lines.next()?.parse().ok()?,
+        y: lines.next()?.parse().ok()?,
+        width: lines.next()?.parse().ok()?,
+        height: lines.next()?.parse().ok()?,
+    };
+
+    if geometry.width < 200 || geometry.height < 150 {
+        return None;
+    }
+
+    Some(geometry)
+}
+
+fn save_window_geometry(geometry: &WindowGeometry) {
+    let path = get_ui_state_path();
+    if let Some(parent) = path.parent() {
+        let _ = create_dir_all(parent);
+    }
+
+    let _ = std::fs::write(
+        path,
+        format!(
+            "{}\n{}\n{}\n{}\n",
+            geometry.x, geometry.y, geometry.width, geometry.height
+        ),
+    );
+}
+
+fn get_stats_path() -> PathBuf {
+    get_config_path()
+        .parent()
+        .map(|dir| dir.join("stats.ini"))
+        .unwrap_or_else(|| PathBuf::from("stats.ini"))
+}
+
+fn load_stats() -> Stats {
+    let contents = std::fs::read_to_string(get_stats_path()).unwrap_or_default();
+    let mut lines = contents.lines();
+
+    Stats {
+        corner_hits: parse_line(lines.next(), 0),
+        longest_without_corner_secs: parse_line(lines.next(), 0.0),
+    }
+}
+
+fn save_stats(stats: &Stats) {
+    let path = get_stats_path();
+    if let Some(parent) = path.parent() {
+        let _ = create_dir_all(parent);
+    }
+
+    let _ = std::fs::write(
+        path,
+        format!(
+            "{}\n{}\n",
+            stats.corner_hits, stats.longest_without_corner_secs
+        ),
+    );
+}
+
+/// Formats a number of seconds as e.g. "1h 02m 03s".
+fn format_duration(secs: f32) -> String {
+    let secs = secs.max(0.0) as u64;
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+
+    if hours > 0 {
+        format!("{}h {:02}m {:02}s", hours, minutes, seconds)
+    } else {
+        format!("{}m {:02}s", minutes, seconds)
+    }
+}
+
+/// Whether the window's title bar would land on a connected monitor, so a
+/// position saved on a since-unplugged display isn't restored off-screen.
+fn is_geometry_visible(app: &App, geometry: &WindowGeometry) -> bool {
+    let title_x = geometry.x + geometry.width.min(100) as i32;
+    let title_y = geometry.y + 10;
+
+    app.available_monitors().iter().any(|monitor| {
+        let position = monitor.position();
+        let size = monitor.size();
+
+        title_x >= position.x
+            && title_x < position.x + size.width as i32
+            && title_y >= position.y
+            && title_y < position.y + size.height as i32
+    })
+}
+
+/// Environment variable that, when set, is used verbatim as the config file
+/// path instead of the per-user config directory. Handy for portable installs
+/// and for testing against throwaway configs.
+const CONFIG_PATH_ENV: &str = "DVD_SCREENSAVER_CONFIG";
+
+fn get_config_path() -> PathBuf {
+    resolve_config_path(
+        CONFIG_FILE.lock().unwrap().as_deref(),
+        env::var_os(CONFIG_PATH_ENV),
+    )
+}
+
+/// Picks the config file: an explicit `--config-file` path, then the value of
+/// `CONFIG_PATH_ENV`, then the per-user config directory.
+fn resolve_config_path(
+    config_file: Option<&Path>,
+    env_path: Option<std::ffi::OsString>,
+) -> PathBuf {
+    if let Some(path) = config_file {
+        path.to_path_buf()
+    } else if let Some(path) = env_path.filter(|path| !path.is_empty()) {
+        PathBuf::from(path)
+    } else if let Some(appdata) = dirs::config_dir() {
+        let config_dir = appdata.join("DVDScreensaver");
+        let _ = create_dir_all(&config_dir);
+        config_dir.join("config.ini")
+    } else {
+        PathBuf::from("screensaver.ini")
+    }
+}
+
+const DEFAULT_PROFILE: &str = "default";
+
+fn is_valid_profile_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_' || c == ' ')
+}
+
+fn get_profiles_dir() -> PathBuf {
+    get_config_path()
+        .parent()
+        .map(|dir| dir.join("profiles"))
+        .unwrap_or_else(|| PathBuf::from("profiles"))
+}
+
+fn get_profile_path(profile: Option<&str>) -> PathBuf {
+    match profile {
+        Some(name) if name != DEFAULT_PROFILE && is_valid_profile_name(name) => {
+            get_profiles_dir().join(format!("{}.ini", name))
+        }
+        _ => get_config_path(),
+    }
+}
+
+fn list_profiles() -> Vec<String> {
+    let mut names = Vec::new();
+
+    if let Ok(entries) = std::fs::read_dir(get_profiles_dir()) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "ini") {
+                if let Some(stem) = path.file_stem() {
+                    let name = stem.to_string_lossy().to_string();
+                    if name != DEFAULT_PROFILE && is_valid_profile_name(&name) {
+                        names.push(name);
+                    }
+                }
+            }
+        }
+    }
+
+    names.sort();
+    names.insert(0, DEFAULT_PROFILE.to_string());
+    names
+}
+
+fn get_last_profile_path() -> PathBuf {
+    get_profiles_dir().join("last_profile")
+}
+
+fn load_last_profile() -> Option<String> {
+    let name = std::fs::read_to_string(get_last_profile_path()).ok()?;
+    let name = name.trim();
+    if is_valid_profile_name(name) {
+        Some(name.to_string())
+    } else {
+        None
+    }
+}
+
+fn save_last_profile(profile: &str) {
+    let path = get_last_profile_path();
+    if let Some(parent) = path.parent() {
+        let _ = create_dir_all(parent);
+    }
+    let _ = std::fs::write(path, profile);
+}
+
+/// The profile requested with `--profile`, or the one last saved from the
+/// configuration dialog.
+fn active_profile() -> Option<String> {
+    ACTIVE_PROFILE
+        .lock()
+        .unwrap()
+        .clone()
+        .or_else(load_last_profile)
+}
+
+fn load_config(profile: Option<&str>) -> ScreenSaverConfig {
+    match std::fs::read_to_string(get_profile_path(profile)) {
+        Ok(contents) => parse_config(&contents),
+        Err(_) => ScreenSaverConfig::default(),
+    }
+}
+
+fn parse_config(contents: &str) -> ScreenSaverConfig {
+    let defaults = ScreenSaverConfig::default();
+    let mut lines = contents.lines();
+    let speed = parse_line(lines.next(), defaults.speed);
+    let image_index = parse_line(lines.next(), defaults.image_index);
+    let size_factor = parse_line(lines.next(), defaults.size_factor);
+    let custom_image_path = lines.next().unwrap_or("").to_string();
+    let logo_count = parse_line(lines.next(), defaults.logo_count);
+    let speed_variance = parse_line(lines.next(), defaults.speed_variance);
+    let logo_collision = parse_line(lines.next(), defaults.logo_collision);
+    let shadow = parse_line(lines.next(), defaults.shadow);
+    let shadow_offset = parse_line(lines.next(), defaults.shadow_offset);
+    let shadow_opacity = parse_line(lines.next(), defaults.shadow_opacity);
+    let glow = parse_line(lines.next(), defaults.glow);
+    let glow_intensity = parse_line(lines.next(), defaults.glow_intensity);
+    let scale_filter = parse_line(lines.next(), defaults.scale_filter);
+    let hide_cursor = parse_line(lines.next(), defaults.hide_cursor);
+    let idle_timeout_secs = parse_line(lines.next(), defaults.idle_timeout_secs);
+    let wall_margin = parse_line(lines.next(), defaults.wall_margin);
+    let logo_opacity = parse_line(lines.next(), defaults.logo_opacity);
+    let brightness = parse_line(lines.next(), defaults.brightness);
+    let contrast = parse_line(lines.next(), defaults.contrast);
+    let saturation = parse_line(lines.next(), defaults.saturation);
+    let invert = parse_line(lines.next(), defaults.invert);
+    let size_mode = parse_line(lines.next(), defaults.size_mode);
+    let randomize_on_launch = parse_line(lines.next(), defaults.randomize_on_launch);
+    let walls = defaults.walls.map(|wall| parse_line(lines.next(), wall));
+    let link_speeds = parse_line(lines.next(), defaults.link_speeds);
+    let speed_x = parse_line(lines.next(), defaults.speed_x);
+    let speed_y = parse_line(lines.next(), defaults.speed_y);
+    let start_position = parse_line(lines.next(), defaults.start_position);
+    let msaa_samples = parse_line(lines.next(), defaults.msaa_samples);
+    let background_image_path = lines.next().unwrap_or("").trim().to_string();
+    let background_fit = parse_line(lines.next(), defaults.background_fit);
+    let show_bounce_counter = parse_line(lines.next(), defaults.show_bounce_counter);
+    let milestone_effects = parse_line(lines.next(), defaults.milestone_effects);
+    let seed = lines.next().and_then(|line| line.trim().parse().ok());
+    let easing = parse_line(lines.next(), defaults.easing);
+    let easing_strength = parse_line(lines.next(), defaults.easing_strength);
+    let color_mode = parse_line(lines.next(), defaults.color_mode);
+    let max_runtime_secs = lines.next().and_then(|line| line.trim().parse().ok());
+    let outline = parse_line(lines.next(), defaults.outline);
+    let outline_color = lines
+        .next()
+        .and_then(|line| parse_hex_color(line.trim()))
+        .unwrap_or(defaults.outline_color);
+    let outline_thickness = parse_line(lines.next(), defaults.outline_thickness);
+    let flip_on_bounce = parse_line(lines.next(), defaults.flip_on_bounce);
+    let watch_folder = parse_line(lines.next(), defaults.watch_folder);
+    let image_cycle = parse_line(lines.next(), defaults.image_cycle);
+    let image_cycle_secs = parse_line(lines.next(), defaults.image_cycle_secs);
+    let neon_glow = parse_line(lines.next(), defaults.neon_glow);
+    let neon_glow_radius = parse_line(lines.next(), defaults.neon_glow_radius);
+    let neon_glow_follows_logo = parse_line(lines.next(), defaults.neon_glow_follows_logo);
+    let neon_glow_color = lines
+        .next()
+        .and_then(|line| parse_hex_color(line.trim()))
+        .unwrap_or(defaults.neon_glow_color);
+    let squash = parse_line(lines.next(), defaults.squash);
+    let squash_intensity = parse_line(lines.next(), defaults.squash_intensity);
+    let night_mode = parse_line(lines.next(), defaults.night_mode);
+    let night_brightness = parse_line(lines.next(), defaults.night_brightness);
+    let night_start_hour = parse_line(lines.next(), defaults.night_start_hour);
+    let night_end_hour = parse_line(lines.next(), defaults.night_end_hour);
+    let echoes = parse_line(lines.next(), defaults.echoes);
+    let echo_count = parse_line(lines.next(), defaults.echo_count);
+    let echo_fade_secs = parse_line(lines.next(), defaults.echo_fade_secs);
+    let freeze_on_corner = parse_line(lines.next(), defaults.freeze_on_corner);
+    let freeze_secs = parse_line(lines.next(), defaults.freeze_secs);
+    let initial_hue = lines.next().and_then(|line| line.trim().parse().ok());
+    let recolor_cooldown_secs = parse_line(lines.next(), defaults.recolor_cooldown_secs);
+    let mouse_exit_distance = parse_line(lines.next(), defaults.mouse_exit_distance);
+    let internal_height = lines.next().and_then(|line| line.trim().parse().ok());
+    let varied_logos = parse_line(lines.next(), defaults.varied_logos);
+    let tight_collision = parse_line(lines.next(), defaults.tight_collision);
+    let startup_fade_secs = parse_line(lines.next(), defaults.startup_fade_secs);
+    let region_width = parse_line(lines.next(), defaults.region_width);
+    let region_height = parse_line(lines.next(), defaults.region_height);
+    let region_x = parse_line(lines.next(), defaults.region_x);
+    let region_y = parse_line(lines.next(), defaults.region_y);
+    let region_border = parse_line(lines.next(), defaults.region_border);
+    let exit_only_on_escape = parse_line(lines.next(), defaults.exit_only_on_escape);
+    let spawn_interval_secs = parse_line(lines.next(), defaults.spawn_interval_secs);
+    let max_logos = parse_line(lines.next(), defaults.max_logos);
+    let start_axis = parse_line(lines.next(), defaults.start_axis);
+    let attract_mode = parse_line(lines.next(), defaults.attract_mode);
+    let obstacle = parse_line(lines.next(), defaults.obstacle);
+    let obstacle_width = parse_line(lines.next(), defaults.obstacle_width);
+    let obstacle_height = parse_line(lines.next(), defaults.obstacle_height);
+    let obstacle_x = parse_line(lines.next(), defaults.obstacle_x);
+    let obstacle_y = parse_line(lines.next(), defaults.obstacle_y);
+    let exit_animation = parse_line(lines.next(), defaults.exit_animation);
+    let corner_celebration = parse_line(lines.next(), defaults.corner_celebration);
+    let pixel_shift = parse_line(lines.next(), defaults.pixel_shift);
+    let pixel_shift_amount = parse_line(lines.next(), defaults.pixel_shift_amount);
+    let pixel_shift_period_secs = parse_line(lines.next(), defaults.pixel_shift_period_secs);
+    // A count, then one icon per line.
+    let icon_set_len = parse_line(lines.next(), 0usize);
+    let icon_set = lines
+        .by_ref()
+        .take(icon_set_len)
+        .filter_map(|line| line.trim().parse().ok())
+        .collect();
+    let gravity_well = parse_line(lines.next(), defaults.gravity_well);
+    let gravity_well_x = parse_line(lines.next(), defaults.gravity_well_x);
+    let gravity_well_y = parse_line(lines.next(), defaults.gravity_well_y);
+    let gravity_well_strength = parse_line(lines.next(), defaults.gravity_well_strength);
+    let image_cache_mb = parse_line(lines.next(), defaults.image_cache_mb);
+    let banner_text = lines.next().unwrap_or("").trim().to_string();
+    let banner_color = lines
+        .next()
+        .and_then(|line| parse_hex_color(line.trim()))
+        .unwrap_or(defaults.banner_color);
+    let banner_position = parse_line(lines.next(), defaults.banner_position);
+    let normalize_speed = parse_line(lines.next(), defaults.normalize_speed);
+    let photo_frame = parse_line(lines.next(), defaults.photo_frame);
+    let photo_pan_secs = parse_line(lines.next(), defaults.photo_pan_secs);
+    let photo_zoom_min = parse_line(lines.next(), defaults.photo_zoom_min);
+    let photo_zoom_max = parse_line(lines.next(), defaults.photo_zoom_max);
+    let photo_recolor = parse_line(lines.next(), defaults.photo_recolor);
+    ScreenSaverConfig {
+        speed,
+        image_index,
+        size_factor,
+        custom_image_path,
+        logo_count,
+        speed_variance,
+        logo_collision,
+        shadow,
+        shadow_offset,
+        shadow_opacity,
+        glow,
+        glow_intensity,
+        scale_filter,
+        hide_cursor,
+        idle_timeout_secs,
+        wall_margin,
+        logo_opacity,
+        brightness,
+        contrast,
+        saturation,
+        invert,
+        size_mode,
+        randomize_on_launch,
+        walls,
+        link_speeds,
+        speed_x,
+        speed_y,
+        start_position,
+        msaa_samples,
+        background_image_path,
+        background_fit,
+        show_bounce_counter,
+        milestone_effects,
+        seed,
+        easing,
+        easing_strength,
+        color_mode,
+        max_runtime_secs,
+        outline,
+        outline_color,
+        outline_thickness,
+        flip_on_bounce,
+        watch_folder,
+        image_cycle,
+        image_cycle_secs,
+        neon_glow,
+        neon_glow_radius,
+        neon_glow_follows_logo,
+        neon_glow_color,
+        squash,
+        squash_intensity,
+        night_mode,
+        night_brightness,
+        night_start_hour,
+        night_end_hour,
+        echoes,
+        echo_count,
+        echo_fade_secs,
+        freeze_on_corner,
+        freeze_secs,
+        initial_hue,
+        recolor_cooldown_secs,
+        mouse_exit_distance,
+        internal_height,
+        varied_logos,
+        tight_collision,
+        startup_fade_secs,
+        region_width,
+        region_height,
+        region_x,
+        region_y,
+        region_border,
+        exit_only_on_escape,
+        spawn_interval_secs,
+        max_logos,
+        start_axis,
+        attract_mode,
+        obstacle,
+        obstacle_width,
+        obstacle_height,
+        obstacle_x,
+        obstacle_y,
+        exit_animation,
+        corner_celebration,
+        pixel_shift,
+        pixel_shift_amount,
+        pixel_shift_period_secs,
+        icon_set,
+        gravity_well,
+        gravity_well_x,
+        gravity_well_y,
+        gravity_well_strength,
+        image_cache_mb,
+        banner_text,
+        banner_color,
+        banner_position,
+        normalize_speed,
+        photo_frame,
+        photo_pan_secs,
+        photo_zoom_min,
+        photo_zoom_max,
+        photo_recolor,
+    }
+}
+
+const SETTINGS_FILE_EXTENSION: &str = "dvdsaver";
+
+/// Separates the config lines of an exported settings file from the embedded
+/// custom icon, e.g. `[icon:png]` followed by the raw image bytes.
+const EMBEDDED_ICON_MARKER: &[u8] = b"[icon:";
+
+/// Writes the config to a single shareable file, embedding the custom icon so
+/// the setup works on another machine.
+fn export_settings(config: &ScreenSaverConfig, path: &Path) -> Result<(), String> {
+    // Icon set files are carried along as data URIs, so the export works on
+    // another machine.
+    let mut exported = config.clone();
+    for icon in &mut exported.icon_set {
+        if let IconSource::Custom(icon_path) = icon {
+            if icon_path.is_file() {
+                *icon = IconSource::Custom(PathBuf::from(encode_data_uri(icon_path)?));
+            }
+        }
+    }
+
+    let mut data = Vec::new();
+    write_config(&mut data, &exported).map_err(|e| format!("Unable to export settings: {}", e))?;
+
+    let icon_path = Path::new(&config.custom_image_path);
+    if config.image_index == BUILT_IN_ICON_COUNT && icon_path.is_file() {
+        let icon =
+            std::fs::read(icon_path).map_err(|e| format!("Unable to read custom icon: {}", e))?;
+        let extension = icon_path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .unwrap_or_else(|| "png".to_string());
+
+        data.extend_from_slice(EMBEDDED_ICON_MARKER);
+        data.extend_from_slice(format!("{}]\n", extension).as_bytes());
+        data.extend_from_slice(&icon);
+    }
+
+    std::fs::write(path, data).map_err(|e| format!("Unable to export settings: {}", e))
+}
+
+/// Reads a file written by `export_settings`. An embedded icon is decoded to
+/// check it's usable, then stored next to the config and used as the custom
+/// icon path.
+fn import_settings(path: &Path) -> Result<ScreenSaverConfig, String> {
+    let data = std::fs::read(path).map_err(|e| format!("Unable to read settings: {}", e))?;
+
+    let (text, icon) = match data
+        .windows(EMBEDDED_ICON_MARKER.len() + 1)
+        .position(|window| window[0] == b'\n' && window[1..] == *EMBEDDED_ICON_MARKER)
+    {
+        Some(index) => (
+            &data[..=index],
+            Some(&data[index + 1 + EMBEDDED_ICON_MARKER.len()..]),
+        ),
+        None => (&data[..], None),
+    };
+
+    let text = std::str::from_utf8(text)
+        .ok()
+        .filter(|text| {
+            text.lines()
+                .next()
+                .is_some_and(|speed| speed.trim().parse::<f32>().is_ok())
+        })
+        .ok_or_else(|| format!("{} is not a DVD Screensaver settings file", path.display()))?;
+    let mut config = parse_config(text);
+
+    if let Some(icon) = icon {
+        let end = icon
+            .iter()
+            .position(|&byte| byte == b'\n')
+            .ok_or_else(|| "The embedded icon is malformed".to_string())?;
+        let extension = std::str::from_utf8(&icon[..end])
+            .ok()
+            .and_then(|header| header.strip_suffix(']'))
+            .filter(|ext| SUPPORTED_EXTENSIONS.contains(ext))
+            .ok_or_else(|| "The embedded icon has an unsupported format".to_string())?;
+        let bytes = &icon[end + 1..];
+
+        decode_image_bytes(bytes, extension, (64, 64))
+            .map_err(|e| format!("The embedded icon could not be decoded: {}", e))?;
+
+        let icon_path = get_config_path()
+            .parent()
+            .map(|dir| dir.join(format!("imported_icon.{}", extension)))
+            .unwrap_or_else(|| PathBuf::from(format!("imported_icon.{}", extension)));
+        if let Some(parent) = icon_path.parent() {
+            let _ = create_dir_all(parent);
+        }
+        std::fs::write(&icon_path, bytes)
+            .map_err(|e| format!("Unable to save the embedded icon: {}", e))?;
+
+        config.custom_image_path = icon_path.to_string_lossy().to_string();
+    }
+
+    Ok(config)
+}
+
+fn format_hex_color([r, g, b]: [u8; 3]) -> String {
+    format!("{:02x}{:02x}{:02x}", r, g, b)
+}
+
+fn parse_hex_color(hex: &str) -> Option<[u8; 3]> {
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+fn parse_line<T: std::str::FromStr>(line: Option<&str>, default: T) -> T {
+    match line.map(str::trim) {
+        Some(value) if !value.is_empty() => value.parse().unwrap_or_else(|_| {
+            log_message!("Ignoring invalid config value '{}'", value);
+            default
+        }),
+        _ => default,
+    }
+}
+
+fn save_config(config: &ScreenSaverConfig, profile: Option<&str>) -> Result<(), String> {
+    let config_path = get_profile_path(profile);
+    let error = |e: std::io::Error| format!("Unable to save {}: {}", config_path.display(), e);
+
+    if let Some(parent) = config_path.parent() {
+        create_dir_all(parent).map_err(error)?;
+    }
+
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(&config_path)
+        .map_err(error)?;
+    write_config(&mut file, config).map_err(error)
+}
+
+fn write_config(out: &mut impl Write, config: &ScreenSaverConfig) -> std::io::Result<()> {
+    writeln!(out, "{}", config.speed)?;
+    writeln!(out, "{}", config.image_index)?;
+    writeln!(out, "{}", config.size_factor)?;
+    writeln!(out, "{}", config.custom_image_path)?;
+    writeln!(out, "{}", config.logo_count)?;
+    writeln!(out, "{}", config.speed_variance)?;
+    writeln!(out, "{}", config.logo_collision)?;
+    writeln!(out, "{}", config.shadow)?;
+    writeln!(out, "{}", config.shadow_offset)?;
+    writeln!(out, "{}", config.shadow_opacity)?;
+    writeln!(out, "{}", config.glow)?;
+    writeln!(out, "{}", config.glow_intensity)?;
+    writeln!(out, "{}", config.scale_filter)?;
+    writeln!(out, "{}", config.hide_cursor)?;
+    writeln!(out, "{}", config.idle_timeout_secs)?;
+    writeln!(out, "{}", config.wall_margin)?;
+    writeln!(out, "{}", config.logo_opacity)?;
+    writeln!(out, "{}", config.brightness)?;
+    writeln!(out, "{}", config.contrast)?;
+    writeln!(out, "{}", config.saturation)?;
+    writeln!(out, "{}", config.invert)?;
+    writeln!(out, "{}", config.size_mode)?;
+    writeln!(out, "{}", config.randomize_on_launch)?;
+    for wall in config.walls {
+        writeln!(out, "{}", wall)?;
+    }
+    writeln!(out, "{}", config.link_speeds)?;
+    writeln!(out, "{}", config.speed_x)?;
+    writeln!(out, "{}", config.speed_y)?;
+    writeln!(out, "{}", config.start_position)?;
+    writeln!(out, "{}", config.msaa_samples)?;
+    writeln!(out, "{}", config.background_image_path)?;
+    writeln!(out, "{}", config.background_fit)?;
+    writeln!(out, "{}", config.show_bounce_counter)?;
+    writeln!(out, "{}", config.milestone_effects)?;
+    writeln!(
+        out,
+        "{}",
+        config.seed.map(|seed| seed.to_string()).unwrap_or_default()
+    )?;
+    writeln!(out, "{}", config.easing)?;
+    writeln!(out, "{}", config.easing_strength)?;
+    writeln!(out, "{}", config.color_mode)?;
+    writeln!(
+        out,
+        "{}",
+        config
+            .max_runtime_secs
+            .map(|secs| secs.to_string())
+            .unwrap_or_default()
+    )?;
+    writeln!(out, "{}", config.outline)?;
+    writeln!(out, "{}", format_hex_color(config.outline_color))?;
+    writeln!(out, "{}", config.outline_thickness)?;
+    writeln!(out, "{}", config.flip_on_bounce)?;
+    writeln!(out, "{}", config.watch_folder)?;
+    writeln!(out, "{}", config.image_cycle)?;
+    writeln!(out, "{}", config.image_cycle_secs)?;
+    writeln!(out, "{}", config.neon_glow)?;
+    writeln!(out, "{}", config.neon_glow_radius)?;
+    writeln!(out, "{}", config.neon_glow_follows_logo)?;
+    writeln!(out, "{}", format_hex_color(config.neon_glow_color))?;
+    writeln!(out, "{}", config.squash)?;
+    writeln!(out, "{}", config.squash_intensity)?;
+    writeln!(out, "{}", config.night_mode)?;
+    writeln!(out, "{}", config.night_brightness)?;
+    writeln!(out, "{}", config.night_start_hour)?;
+    writeln!(out, "{}", config.night_end_hour)?;
+    writeln!(out, "{}", config.echoes)?;
+    writeln!(out, "{}", config.echo_count)?;
+    writeln!(out, "{}", config.echo_fade_secs)?;
+    writeln!(out, "{}", config.freeze_on_corner)?;
+    writeln!(out, "{}", config.freeze_secs)?;
+    writeln!(
+        out,
+        "{}",
+        config
+            .initial_hue
+            .map(|hue| hue.to_string())
+            .unwrap_or_default()
+    )?;
+    writeln!(out, "{}", config.recolor_cooldown_secs)?;
+    writeln!(out, "{}", config.mouse_exit_distance)?;
+    writeln!(
+        out,
+        "{}",
+        config
+            .internal_height
+            .map(|height| height.to_string())
+            .unwrap_or_default()
+    )?;
+    writeln!(out, "{}", config.varied_logos)?;
+    writeln!(out, "{}", config.tight_collision)?;
+    writeln!(out, "{}", config.startup_fade_secs)?;
+    writeln!(out, "{}", config.region_width)?;
+    writeln!(out, "{}", config.region_height)?;
+    writeln!(out, "{}", config.region_x)?;
+    writeln!(out, "{}", config.region_y)?;
+    writeln!(out, "{}", config.region_border)?;
+    writeln!(out, "{}", config.exit_only_on_escape)?;
+    writeln!(out, "{}", config.spawn_interval_secs)?;
+    writeln!(out, "{}", config.max_logos)?;
+    writeln!(out, "{}", config.start_axis)?;
+    writeln!(out, "{}", config.attract_mode)?;
+    writeln!(out, "{}", config.obstacle)?;
+    writeln!(out, "{}", config.obstacle_width)?;
+    writeln!(out, "{}", config.obstacle_height)?;
+    writeln!(out, "{}", config.obstacle_x)?;
+    writeln!(out, "{}", config.obstacle_y)?;
+    writeln!(out, "{}", config.exit_animation)?;
+    writeln!(out, "{}", config.corner_celebration)?;
+    writeln!(out, "{}", config.pixel_shift)?;
+    writeln!(out, "{}", config.pixel_shift_amount)?;
+    writeln!(out, "{}", config.pixel_shift_period_secs)?;
+    writeln!(out, "{}", config.icon_set.len())?;
+    for icon in &config.icon_set {
+        writeln!(out, "{}", icon)?;
+    }
+    writeln!(out, "{}", config.gravity_well)?;
+    writeln!(out, "{}", config.gravity_well_x)?;
+    writeln!(out, "{}", config.gravity_well_y)?;
+    writeln!(out, "{}", config.gravity_well_strength)?;
+    writeln!(out, "{}", config.image_cache_mb)?;
+    writeln!(out, "{}", config.banner_text)?;
+    writeln!(out, "{}", format_hex_color(config.banner_color))?;
+    writeln!(out, "{}", config.banner_position)?;
+    writeln!(out, "{}", config.normalize_speed)?;
+    writeln!(out, "{}", config.photo_frame)?;
+    writeln!(out, "{}", config.photo_pan_secs)?;
+    writeln!(out, "{}", config.photo_zoom_min)?;
+    writeln!(out, "{}", config.photo_zoom_max)?;
+    writeln!(out, "{}", config.photo_recolor)?;
+    Ok(())
+}
+
+/// Once the log grows past this size it is moved aside to `.log.old` on the
+/// next start, keeping at most two files around.
+const MAX_LOG_BYTES: u64 = 512 * 1024;
+
+fn get_log_path() -> PathBuf {
+    get_config_path()
+        .parent()
+        .map(|dir| dir.join("dvd_screensaver.log"))
+        .unwrap_or_else(|| PathBuf::from("dvd_screensaver.log"))
+}
+
+fn rotate_log() {
+    let path = get_log_path();
+    if std::fs::metadata(&path).is_ok_and(|metadata| metadata.len() > MAX_LOG_BYTES) {
+        let _ = std::fs::rename(&path, path.with_extension("log.old"));
+    }
+}
+
+fn write_log(message: &str) {
+    eprintln!("{}", message);
+
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+
+    if let Ok(mut file) = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(get_log_path())
+    {
+        let _ = writeln!(
+            file,
+            "[{}] [pid {}] {}",
+            timestamp,
+            std::process::id(),
+            message
+        );
+    }
+}
+
+/// Saves the image currently on the clipboard as a PNG next to the config file
+/// and returns its path. The file name is fixed, so each paste replaces the
+/// previous one rather than accumulating copies.
+fn save_clipboard_image() -> Result<PathBuf, String> {
+    let mut clipboard =
+        arboard::Clipboard::new().map_err(|e| format!("Unable to access clipboard: {}", e))?;
+    let data = clipboard
+        .get_image()
+        .map_err(|_| "The clipboard does not contain an image".to_string())?;
+
+    let image = image::RgbaImage::from_raw(
+        data.width as u32,
+        data.height as u32,
+        data.bytes.into_owned(),
+    )
+    .ok_or_else(|| "The clipboard image data is malformed".to_string())?;
+
+    let path = get_config_path()
+        .parent()
+        .map(|dir| dir.join("clipboard_icon.png"))
+        .unwrap_or_else(|| PathBuf::from("clipboard_icon.png"));
+
+    image
+        .save(&path)
+        .map_err(|e| format!("Unable to save clipboard image: {}", e))?;
+
+    Ok(path)
+}
+
+const SUPPORTED_EXTENSIONS: &[&str] = &[
+    "png",
+    "jpg",
+    "jpeg",
+    "gif",
+    "bmp",
+    "ico",
+    "cur",
+    "ani",
+    "tiff",
+    "tif",
+    "webp",
+    "svg",
+    #[cfg(feature = "jxl")]
+    "jxl",
+    #[cfg(feature = "avif")]
+    "avif",
+];
+
+const SUPPORTED_FORMAT_NAMES: &[&str] = &[
+    "PNG",
+    "JPG",
+    "GIF",
+    "BMP",
+    "ICO",
+    "CUR",
+    "ANI",
+    "TIFF",
+    "WebP",
+    "SVG",
+    #[cfg(feature = "jxl")]
+    "JPEG XL",
+    #[cfg(feature = "avif")]
+    "AVIF",
+];
+
+/// One image in an ICO or CUR file.
+struct IconEntry {
+    width: u32,
+    height: u32,
+    /// The entry's 16 directory bytes.
+    directory: [u8; 16],
+    data: std::ops::Range<usize>,
+}
+
+/// Reads the images in an ICO or CUR file, which share a format.
+fn icon_entries(bytes: &[u8]) -> Result<Vec<IconEntry>, String> {
+    let u16_at = |i: usize| u16::from_le_bytes([bytes[i], bytes[i + 1]]);
+    let u32_at =
+        |i: usize| u32::from_le_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]]);
+
+    if bytes.len() < 6 || u16_at(0) != 0 || !matches!(u16_at(2), 1 | 2) {
+        return Err("Not an icon or cursor".to_string());
+    }
+
+    (0..u16_at(4) as usize)
+        .map(|i| {
+            let start = 6 + i * 16;
+            let directory: [u8; 16] = bytes
+                .get(start..start + 16)
+                .and_then(|entry| entry.try_into().ok())
+                .ok_or("Truncated icon directory")?;
+            // A stored size of 0 means 256.
+            let size = |byte: u8| if byte == 0 { 256 } else { byte as u32 };
+            let offset = u32_at(start + 12) as usize;
+            let data = offset..offset.saturating_add(u32_at(start + 8) as usize);
+            if data.end > bytes.len() {
+                return Err("Truncated icon image".to_string());
+            }
+
+            Ok(IconEntry {
+                width: size(directory[0]),
+                height: size(directory[1]),
+                directory,
+                data,
+            })
+        })
+        .collect()
+}
+
+/// Decodes the image of an ICO or CUR file closest to `target_size`: the
+/// smallest that is at least as large, so it's only ever scaled down, or else
+/// the largest. The `image` crate would always pick the largest.
+fn decode_icon(bytes: &[u8], target_size: (u32, u32)) -> Result<DynamicImage, String> {
+    let entries = icon_entries(bytes)?;
+    let target = target_size.0.max(target_size.1);
+    let edge = |entry: &&IconEntry| entry.width.max(entry.height);
+    let entry = entries
+        .iter()
+        .filter(|entry| edge(entry) >= target)
+        .min_by_key(edge)
+        .or_else(|| entries.iter().max_by_key(edge))
+        .ok_or("The icon contains no images")?;
+
+    // Hand the decoder an icon holding only the chosen image.
+    let mut icon = vec![0, 0, 1, 0, 1, 0];
+    icon.extend_from_slice(&entry.directory[..12]);
+    icon.extend_from_slice(&22u32.to_le_bytes());
+    icon.extend_from_slice(&bytes[entry.data.clone()]);
+    image::load_from_memory_with_format(&icon, image::ImageFormat::Ico).map_err(|e| e.to_string())
+}
+
+/// The first frame of an animated cursor, which is stored as an icon in a
+/// RIFF `ACON` file. Only that frame is shown; the logo isn't animated.
+fn first_ani_frame(bytes: &[u8]) -> Result<&[u8], String> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"ACON" {
+        return Err("Not an animated cursor".to_string());
+    }
+
+    let mut chunks = vec![&bytes[12..]];
+    while let Some(mut chunk) = chunks.pop() {
+        while chunk.len() >= 8 {
+            let id = &chunk[0..4];
+            let size = u32::from_le_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]) as usize;
+            let body = chunk.get(8..8 + size).ok_or("Truncated animated cursor")?;
+            match id {
+                b"icon" => return Ok(body),
+                b"LIST" if body.len() >= 4 => chunks.push(&body[4..]),
+                _ => (),
+            }
+            // Chunks are padded to an even length.
+            chunk = chunk.get(8 + size + size % 2..).unwrap_or(&[]);
+        }
+    }
+    Err("The animated cursor contains no frames".to_string())
+}
+
+/// Reads an ICO, CUR or ANI file as icon data, or returns `None` for other
+/// files.
+fn read_icon_file(path: &Path) -> Option<Result<Vec<u8>, String>> {
+    let extension = path.extension()?.to_string_lossy().to_lowercase();
+    if !matches!(extension.as_str(), "ico" | "cur" | "ani") {
+        return None;
+    }
+
+    Some(std::fs::read(path).map_err(|e| e.to_string()).and_then(
+        |bytes| match extension.as_str() {
+            "ani" => first_ani_frame(&bytes).map(|frame| frame.to_vec()),
+            _ => Ok(bytes),
+        },
+    ))
+}
+
+#[cfg_attr(not(any(feature = "jxl", feature = "avif")), allow(unused_variables))]
+fn decode_extra_format(bytes: &[u8], extension: &str) -> Result<DynamicImage, String> {
+    match extension {
+        #[cfg(feature = "jxl")]
+        "jxl" => decode_jxl(bytes),
+        #[cfg(feature = "avif")]
+        "avif" => decode_avif(bytes),
+        _ => Err(format!("Unsupported format '{}'", extension)),
+    }
+}
+
+#[cfg(feature = "jxl")]
+fn decode_jxl(bytes: &[u8]) -> Result<DynamicImage, String> {
+    let jxl = jxl_oxide::JxlImage::builder()
+        .read(bytes)
+        .map_err(|e| e.to_string())?;
+    let render = jxl.render_frame(0).map_err(|e| e.to_string())?;
+    let mut stream = render.stream();
+    let (width, height, channels) = (stream.width(), stream.height(), stream.channels());
+    let mut buffer = vec![0u8; (width * height * channels) as usize];
+    stream.write_to_buffer(&mut buffer);
+
+    match channels {
+        1 => image::GrayImage::from_raw(width, height, buffer).map(DynamicImage::ImageLuma8),
+        2 => image::GrayAlphaImage::from_raw(width, height, buffer).map(DynamicImage::ImageLumaA8),
+        3 => image::RgbImage::from_raw(width, height, buffer).map(DynamicImage::ImageRgb8),
+        4 => image::RgbaImage::from_raw(width, height, buffer).map(DynamicImage::ImageRgba8),
+        _ => None,
+    }
+    .ok_or_else(|| format!("Unsupported JPEG XL layout with {} channels", channels))
+}
+
+/// Decoded with a newer `image` than nannou's, whose AVIF support needs the
+/// dav1d library installed.
+#[cfg(feature = "avif")]
+fn decode_avif(bytes: &[u8]) -> Result<DynamicImage, String> {
+    let decoded = image_avif::load_from_memory_with_format(bytes, image_avif::ImageFormat::Avif)
+        .map_err(|e| e.to_string())?
+        .to_rgba8();
+    let (width, height) = decoded.dimensions();
+
+    image::RgbaImage::from_raw(width, height, decoded.into_raw())
+        .map(DynamicImage::ImageRgba8)
+        .ok_or_else(|| "Unable to convert the decoded AVIF image".to_string())
+}
+
+/// The size of the primary monitor in points, used to size icons before the
+/// fullscreen window exists.
+fn primary_screen_size(app: &App) -> Vec2 {
+    // Wayland has no primary monitor, so fall back to the first one.
+    app.primary_monitor()
+        .or_else(|| app.available_monitors().into_iter().next())
+        .map(|monitor| {
+            let scale_factor = monitor.scale_factor() as f32;
+            Vec2::new(
+                monitor.size().width as f32 / scale_factor,
+                monitor.size().height as f32 / scale_factor,
+            )
+        })
+        .filter(|size| size.x > 0.0 && size.y > 0.0)
+        .unwrap_or(Vec2::new(1920.0, 1080.0))
+}
+
+/// Rasterizes an SVG so that it fits within `target_size`, preserving its
+/// aspect ratio.
+fn rasterize_svg(data: &[u8], target_size: (u32, u32)) -> Result<DynamicImage, String> {
+    let tree = resvg::usvg::Tree::from_data(data, &resvg::usvg::Options::default())
+        .map_err(|e| format!("Invalid SVG: {}", e))?;
+
+    let size = tree.size();
+    let scale = (target_size.0.max(1) as f32 / size.width())
+        .min(target_size.1.max(1) as f32 / size.height());
+    let width = (size.width() * scale).round().max(1.0) as u32;
+    let height = (size.height() * scale).round().max(1.0) as u32;
+
+    let mut pixmap = resvg::tiny_skia::Pixmap::new(width, height)
+        .ok_or_else(|| "SVG has an invalid size".to_string())?;
+    resvg::render(
+        &tree,
+        resvg::tiny_skia::Transform::from_scale(scale, scale),
+        &mut pixmap.as_mut(),
+    );
+
+    let pixels = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|pixel| {
+            let color = pixel.demultiply();
+            [color.red(), color.green(), color.blue(), color.alpha()]
+        })
+        .collect();
+
+    image::RgbaImage::from_raw(width, height, pixels)
+        .map(DynamicImage::ImageRgba8)
+        .ok_or_else(|| "Unable to convert rasterized SVG".to_string())
+}
+
+/// Whether an image "path" is really a `data:` URI with the image inline.
+fn is_data_uri(path: &str) -> bool {
+    path.starts_with("data:")
+}
+
+/// Splits a `data:<type>;base64,<data>` URI into its media type and decoded
+/// bytes.
+fn decode_data_uri(uri: &str) -> Result<(&str, Vec<u8>), String> {
+    let (header, data) = uri
+        .strip_prefix("data:")
+        .and_then(|uri| uri.split_once(','))
+        .ok_or_else(|| "Malformed data URI: expected data:<type>;base64,<data>".to_string())?;
+    let media_type = header
+        .strip_suffix(";base64")
+        .ok_or_else(|| "Only base64 data URIs are supported".to_string())?;
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(data.trim())
+        .map_err(|e| format!("Malformed base64 in data URI: {}", e))?;
+    Ok((media_type, bytes))
+}
+
+/// Reads an image file into a `data:` URI, so it can travel inside a config.
+fn encode_data_uri(path: &Path) -> Result<String, String> {
+    let bytes =
+        std::fs::read(path).map_err(|e| format!("Unable to read {}: {}", path.display(), e))?;
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    // Left for the `image` crate to recognize if it isn't one of ours.
+    let media_type = IMAGE_MEDIA_TYPES
+        .iter()
+        .find(|(known, _)| *known == extension)
+        .map_or("application/octet-stream", |(_, media_type)| media_type);
+    Ok(format!(
+        "data:{};base64,{}",
+        media_type,
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    ))
+}
+
+/// The media type data URIs use for each supported extension. Where several
+/// share one, the first is the extension it's decoded as.
+const IMAGE_MEDIA_TYPES: &[(&str, &str)] = &[
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("bmp", "image/bmp"),
+    ("ico", "image/x-icon"),
+    ("ico", "image/vnd.microsoft.icon"),
+    ("cur", "image/x-win-cursor"),
+    ("ani", "application/x-navi-animation"),
+    ("tiff", "image/tiff"),
+    ("tif", "image/tiff"),
+    ("webp", "image/webp"),
+    ("svg", "image/svg+xml"),
+    ("jxl", "image/jxl"),
+    ("avif", "image/avif"),
+];
+
+fn media_type_extension(media_type: &str) -> Option<&'static str> {
+    IMAGE_MEDIA_TYPES
+        .iter()
+        .find(|(_, known)| known.eq_ignore_ascii_case(media_type))
+        .map(|(extension, _)| *extension)
+}
+
+fn load_data_uri(uri: &str, target_size: (u32, u32)) -> Result<DynamicImage, String> {
+    let (media_type, bytes) = decode_data_uri(uri)?;
+    let extension = media_type_extension(media_type).unwrap_or_default();
+    decode_image_bytes(&bytes, extension, target_size)
+}
+
+/// Decodes an image in the format `extension` names, whether it came from a
+/// file or a data URI. Anything not handled here is left to the `image`
+/// crate, which recognizes the format from its contents.
+fn decode_image_bytes(
+    bytes: &[u8],
+    extension: &str,
+    target_size: (u32, u32),
+) -> Result<DynamicImage, String> {
+    match extension {
+        "svg" => rasterize_svg(bytes, target_size),
+        "ico" | "cur" => decode_icon(bytes, target_size),
+        "ani" => decode_icon(first_ani_frame(bytes)?, target_size),
+        "jxl" | "avif" => decode_extra_format(bytes, extension),
+        _ => image::load_from_memory(bytes).map_err(|e| e.to_string()),
+    }
+}
+
+fn load_image_safe(path: &str, target_size: (u32, u32)) -> Result<DynamicImage, ImageError> {
+    if path.is_empty() {
+        return Err(ImageError::IoError(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "Empty path",
+        )));
+    }
+
+    if is_data_uri(path) {
+        return load_data_uri(path, target_size).map_err(|e| {
+            ImageError::IoError(std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+        });
+    }
+
+    let path = Path::new(path);
+    if !path.exists() {
+        return Err(ImageError::IoError(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "File not found",
+        )));
+    }
+
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let data = std::fs::read(path).map_err(ImageError::IoError)?;
+    decode_image_bytes(&data, &extension, target_size)
+        .map_err(|e| ImageError::IoError(std::io::Error::new(std::io::ErrorKind::InvalidData, e)))
+}
+
+/// Loads the selected icon. `target_size` is only used for vector icons,
+/// which are rasterized directly at the size they'll be displayed.
+fn get_image_data(icon: &IconSource, target_size: (u32, u32)) -> Result<DynamicImage, String> {
+    match icon {
+        IconSource::BuiltIn(0) => {
+            let data = include_bytes!("../assets/dvd_logo.png");
+            image::load_from_memory(data)
+                .map_err(|e| format!("Unable to load built-in icon 1: {}", e))
+        }
+        IconSource::BuiltIn(1) => {
+            let data = include_bytes!("../assets/dvd_logo2.png");
+            image::load_from_memory(data)
+                .map_err(|e| format!("Unable to load built-in icon 2: {}", e))
+        }
+        IconSource::Custom(path) => {
+            if path.as_os_str().is_empty() {
+                return Err("No custom icon path specified".to_string());
+            }
+
+            load_image_safe(&path.to_string_lossy(), target_size)
+                .map_err(|e| format!("Unable to load custom icon '{}': {}", path.display(), e))
+        }
+        IconSource::BuiltIn(_) => {
+            let data = include_bytes!("../assets/dvd_logo.png");
+            image::load_from_memory(data).map_err(|e| format!("Unable to load default icon: {}", e))
+        }
+    }
+}
+
+const BUILT_IN_ICON_COUNT: usize = 2;
+
+const BUILT_IN_ICON_NAMES: [&str; BUILT_IN_ICON_COUNT] =
+    ["Built-in DVD Logo", "Built-in DVD Logo 2"];
+
+/// The `image_index` that selects `icon_set`, after the built-in icons and the
+/// custom icon.
+const ICON_SET_INDEX: usize = BUILT_IN_ICON_COUNT + 1;
+
+/// Loads `icon` and scales it to fit `target_size`.
+fn load_icon(
+    icon: &IconSource,
+    target_size: (u32, u32),
+    filter: ScaleFilter,
+) -> Result<DynamicImage, String> {
+    let key = image_key(icon, target_size, None);
+    if let Some(image) = IMAGE_CACHE.lock().unwrap().get(key) {
+        return Ok(image);
+    }
+
+    let image = get_image_data(icon, target_size)?;
+    let scaled = scale_image(&image, target_size.0, target_size.1, filter);
+    IMAGE_CACHE.lock().unwrap().insert(key, &scaled);
+    Ok(scaled)
+}
+
+/// Applies `randomize_on_launch` to this run only: picks a random starting
+/// hue and a random built-in icon. A custom icon is kept, unless its path is a
+/// folder, in which case a random image from that folder is used. An icon set
+/// starts from a random icon, keeping its order.
+fn randomize_launch(config: &mut ScreenSaverConfig) {
+    if !config.randomize_on_launch {
+        return;
+    }
+
+    let mut rng = RNG.lock().unwrap();
+    *LAST_HUE.lock().unwrap() = rng.gen_range(0..360);
+
+    if config.image_index < BUILT_IN_ICON_COUNT {
+        config.image_index = rng.gen_range(0..BUILT_IN_ICON_COUNT);
+    } else if config.uses_icon_set() {
+        let start = rng.gen_range(0..config.icon_set.len());
+        config.icon_set.rotate_left(start);
+    } else if let Some(path) =
+        random_image_in_folder(Path::new(&config.custom_image_path), &mut *rng)
+    {
+        config.custom_image_path = path.to_string_lossy().to_string();
+    }
+}
+
+fn is_supported_image(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| {
+        SUPPORTED_EXTENSIONS.contains(&ext.to_string_lossy().to_lowercase().as_str())
+    })
+}
+
+fn random_image_in_folder(folder: &Path, rng: &mut impl Rng) -> Option<PathBuf> {
+    let images: Vec<PathBuf> = std::fs::read_dir(folder)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| is_supported_image(path))
+        .collect();
+
+    images.choose(rng).cloned()
+}
+
+/// How often a watched folder is checked for a newer image, in seconds.
+const WATCH_POLL_INTERVAL: f32 = 2.0;
+
+fn newest_image_in_folder(folder: &Path) -> Option<(PathBuf, SystemTime)> {
+    std::fs::read_dir(folder)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| is_supported_image(&entry.path()))
+        .filter_map(|entry| Some((entry.path(), entry.metadata().ok()?.modified().ok()?)))
+        .max_by_key(|(_, modified)| *modified)
+}
+
+/// Applies `watch_folder`: if the custom icon path is a folder, points it at
+/// the folder's newest image. Returns the folder and that image.
+fn resolve_watched_folder(
+    config: &mut ScreenSaverConfig,
+) -> Option<(PathBuf, Option<(PathBuf, SystemTime)>)> {
+    let folder = PathBuf::from(&config.custom_image_path);
+    if !config.watch_folder || config.image_index != BUILT_IN_ICON_COUNT || !folder.is_dir() {
+        return None;
+    }
+
+    let newest = newest_image_in_folder(&folder);
+    if let Some((path, _)) = &newest {
+        config.custom_image_path = path.to_string_lossy().to_string();
+    }
+    Some((folder, newest))
+}
+
+impl WatchedFolder {
+    /// Checks the folder at most every `WATCH_POLL_INTERVAL` and returns the
+    /// newest image, scaled for the logo, if it has changed. A deleted or
+    /// empty folder leaves the current image in place.
+    fn poll(&mut self, delta_time: f32, filter: ScaleFilter) -> Option<(IconSource, DynamicImage)> {
+        self.since_poll += delta_time;
+        if self.since_poll < WATCH_POLL_INTERVAL {
+            return None;
+        }
+        self.since_poll = 0.0;
+
+        let newest = newest_image_in_folder(&self.folder)?;
+        if self.current.as_ref() == Some(&newest) {
+            return None;
+        }
+
+        // Remember it even if it fails to load so a half-written file is
+        // only retried once its modification time changes.
+        let path = newest.0.clone();
+        self.current = Some(newest);
+        match load_image_safe(&path.to_string_lossy(), self.target_size) {
+            Ok(image) => {
+                log_message!("Showing newest image {}", path.display());
+                // The file may have been overwritten in place, which would
+                // leave stale copies under the same path.
+                IMAGE_CACHE.lock().unwrap().clear();
+                let (width, height) = self.target_size;
+                Some((
+                    IconSource::Custom(path),
+                    scale_image(&image, width, height, filter),
+                ))
+            }
+            Err(error) => {
+                log_message!("Unable to load {}: {}", path.display(), error);
+                None
+            }
+        }
+    }
+}
+
+/// Applies `image_cycle`: returns the icon set, or if the custom icon path is
+/// a folder, points it at the folder's first image and returns all of its
+/// images, in name order.
+fn resolve_cycle_images(config: &mut ScreenSaverConfig) -> Option<Vec<IconSource>> {
+    if config.image_cycle == ImageCycle::Never {
+        return None;
+    }
+    if config.uses_icon_set() {
+        return Some(config.icon_set.clone());
+    }
+
+    let folder = Path::new(&config.custom_image_path);
+    if config.image_index != BUILT_IN_ICON_COUNT || !folder.is_dir() {
+        return None;
+    }
+
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(folder)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| is_supported_image(path))
+        .collect();
+    paths.sort();
+
+    config.custom_image_path = paths.first()?.to_string_lossy().to_string();
+    Some(paths.into_iter().map(IconSource::Custom).collect())
+}
+
+impl ImageCycler {
+    /// Loads and scales every image up front so swapping never stalls a
+    /// frame. Returns `None` if fewer than two images could be loaded.
+    fn new(
+        icons: &[IconSource],
+        config: &ScreenSaverConfig,
+        target_size: (u32, u32),
+    ) -> Option<Self> {
+        let images: Vec<(IconSource, DynamicImage)> = icons
+            .iter()
+            .filter_map(
+                |icon| match load_icon(icon, target_size, config.scale_filter) {
+                    Ok(image) => Some((icon.clone(), image)),
+                    Err(error) => {
+                        log_message!("Skipping cycle image: {}", error);
+                        None
+                    }
+                },
+            )
+            .collect();
+
+        (images.len() > 1).then_some(ImageCycler {
+            images,
+            next: 1,
+            mode: config.image_cycle,
+            interval: config.image_cycle_secs.max(1.0),
+            since_swap: 0.0,
+        })
+    }
+
+    /// Reloads every image at a new size, keeping the old one if that fails.
+    fn resize(&mut self, target_size: (u32, u32), filter: ScaleFilter) {
+        for (icon, image) in &mut self.images {
+            match load_icon(icon, target_size, filter) {
+                Ok(resized) => *image = resized,
+                Err(error) => log_message!("Unable to reload cycle image: {}", error),
+            }
+        }
+    }
+
+    /// Returns the next image if it's time to swap.
+    fn advance(&mut self, bounced: bool, delta_time: f32) -> Option<(IconSource, DynamicImage)> {
+        match self.mode {
+            ImageCycle::Never => return None,
+            ImageCycle::OnBounce if !bounced => return None,
+            ImageCycle::OnBounce => (),
+            ImageCycle::OnTimer => {
+                self.since_swap += delta_time;
+                if self.since_swap < self.interval {
+                    return None;
+                }
+                self.since_swap = 0.0;
+            }
+        }
+
+        let image = self.images[self.next].clone();
+        self.next = (self.next + 1) % self.images.len();
+        Some(image)
+    }
+}
+
+/// Sources more than this many times larger than the logo are first shrunk
+/// to twice its size with a Lanczos filter, as `thumbnail` alone can leave
+/// moiré in detailed photos. Smaller sources skip the slower pass.
+const PRE_DOWNSCALE_RATIO: u32 = 4;
+
+fn scale_image(image: &DynamicImage, width: u32, height: u32, filter: ScaleFilter) -> DynamicImage {
+    match filter {
+        ScaleFilter::Smooth
+            if image.width() > width.saturating_mul(PRE_DOWNSCALE_RATIO)
+                || image.height() > height.saturating_mul(PRE_DOWNSCALE_RATIO) =>
+        {
+            image
+                .resize(width * 2, height * 2, FilterType::Lanczos3)
+                .thumbnail(width, height)
+        }
+        ScaleFilter::Smooth => image.thumbnail(width, height),
+        ScaleFilter::Nearest => image.resize(width, height, FilterType::Nearest),
+    }
+}
+
+fn show_image_error_dialog(error: &str) {
+    MessageDialog::new()
+        .set_level(MessageLevel::Error)
+        .set_title("DVD Screensaver")
+        .set_description(format!(
+            "{}\n\nThe built-in DVD logo will be used instead.",
+            error
+        ))
+        .set_buttons(MessageButtons::Ok)
+        .show();
+}
+
+/// Loaded and recolored images by `image_key`, least recently used first,
+/// so a slideshow coming back around or a logo landing on a hue it has had
+/// before skips the decoding and recoloring. Holds at most `max_bytes` of
+/// pixels.
+#[derive(Default)]
+struct ImageCache {
+    entries: VecDeque<(u64, DynamicImage)>,
+    bytes: usize,
+    max_bytes: usize,
+}
+
+impl ImageCache {
+    /// Empties the cache and sets its size, for a new run's config.
+    fn reset(&mut self, max_megabytes: u32) {
+        *self = ImageCache {
+            max_bytes: max_megabytes as usize * 1024 * 1024,
+            ..Default::default()
+        };
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.bytes = 0;
+    }
+
+    fn get(&mut self, key: u64) -> Option<DynamicImage> {
+        let index = self.entries.iter().position(|(k, _)| *k == key)?;
+        let entry = self.entries.remove(index)?;
+        let image = entry.1.clone();
+        self.entries.push_back(entry);
+        Some(image)
+    }
+
+    fn insert(&mut self, key: u64, image: &DynamicImage) {
+        let size = image.as_bytes().len();
+        if size > self.max_bytes {
+            return;
+        }
+
+        while self.bytes + size > self.max_bytes {
+            let Some((_, evicted)) = self.entries.pop_front() else {
+                break;
+            };
+            self.bytes -= evicted.as_bytes().len();
+        }
+        self.bytes += size;
+        self.entries.push_back((key, image.clone()));
+    }
+}
+
+/// The cache key for `icon` at `size`, recolored to `hue` if given. The other
+/// recolor settings are fixed for a run, and the cache is reset with them.
+fn image_key(icon: &IconSource, size: (u32, u32), hue: Option<i32>) -> u64 {
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    icon.to_string().hash(&mut hasher);
+    size.hash(&mut hasher);
+    hue.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Replaces the shared RNG with a seeded one, if a seed is configured.
+fn seed_rng(seed: Option<u64>) {
+    if let Some(seed) = seed {
+        *RNG.lock().unwrap() = StdRng::seed_from_u64(seed);
+    }
+}
+
+/// Picks a random hue at least 60° away from `last_hue`, so each color change
+/// is clearly visible.
+fn next_hue(rng: &mut impl Rng, last_hue: i32) -> i32 {
+    loop {
+        let new_hue = rng.gen_range(0..360);
+        let hue_diff = (new_hue - last_hue).abs();
+        let min_diff = hue_diff.min(360 - hue_diff);
+
+        if min_diff >= 60 {
+            return new_hue;
+        }
+    }
+}
+
+/// Picks a hue within `variation` degrees of `base_hue`, preferring one that
+/// differs noticeably from `last_hue`.
+fn next_tint_hue(rng: &mut impl Rng, base_hue: i32, variation: f32, last_hue: i32) -> i32 {
+    let variation = variation.clamp(0.0, 180.0) as i32;
+    if variation == 0 {
+        return base_hue;
+    }
+
+    let mut new_hue = base_hue;
+    for _ in 0..10 {
+        new_hue = (base_hue + rng.gen_range(-variation..=variation)).rem_euclid(360);
+        let hue_diff = (new_hue - last_hue).abs();
+        if hue_diff.min(360 - hue_diff) >= variation / 2 {
+            break;
+        }
+    }
+    new_hue
+}
+
+fn change_color(
+    image: &DynamicImage,
+    icon: &IconSource,
+    settings: &RecolorSettings,
+) -> DynamicImage {
+    let new_hue = {
+        let mut rng = RNG.lock().unwrap();
+        let mut last_hue = LAST_HUE.lock().unwrap();
+        *last_hue = match settings.color_mode {
+            ColorMode::Rotate | ColorMode::RainbowCycle { .. } => next_hue(&mut *rng, *last_hue),
+            ColorMode::BrightnessPulse { .. } => *last_hue,
+            ColorMode::Tint { base, variation } => {
+                next_tint_hue(&mut *rng, rgb_to_hsv(base).0 as i32, variation, *last_hue)
+            }
+        };
+        *last_hue
+    };
+
+    cached_color_image(image, icon, settings, new_hue)
+}
+
+/// `color_image`, through the image cache. `image` must be `icon` as loaded
+/// for this run.
+fn cached_color_image(
+    image: &DynamicImage,
+    icon: &IconSource,
+    settings: &RecolorSettings,
+    hue: i32,
+) -> DynamicImage {
+    let key = image_key(icon, image.dimensions(), Some(hue));
+    if let Some(colored) = IMAGE_CACHE.lock().unwrap().get(key) {
+        return colored;
+    }
+
+    let colored = color_image(image, settings, hue);
+    IMAGE_CACHE.lock().unwrap().insert(key, &colored);
+    colored
+}
+
+/// Colors `image` with `hue`, leaving `LAST_HUE` alone.
+fn color_image(image: &DynamicImage, settings: &RecolorSettings, hue: i32) -> DynamicImage {
+    let colored = match settings.color_mode {
+        ColorMode::Rotate | ColorMode::RainbowCycle { .. } | ColorMode::BrightnessPulse { .. } => {
+            adjust_saturation(image, settings.saturation).huerotate(hue)
+        }
+        ColorMode::Tint { base, .. } => {
+            let (_, saturation, value) = rgb_to_hsv(base);
+            let tint = hsv_to_rgb(hue as f32, saturation, value);
+            adjust_saturation(&tint_image(image, tint), settings.saturation)
+        }
+    };
+
+    let mut recolored = colored
+        .brighten(settings.brightness)
+        .adjust_contrast(settings.contrast);
+
+    if settings.invert {
+        recolored.invert();
+    }
+
+    apply_opacity(recolored, settings.opacity)
+}
+
+/// `image` brightened in `PULSE_STEPS` steps up to the flash intensity,
+/// premultiplied, or none outside `ColorMode::BrightnessPulse`.
+fn pulse_frames(image: &DynamicImage, settings: &RecolorSettings) -> Vec<DynamicImage> {
+    let ColorMode::BrightnessPulse { intensity } = settings.color_mode else {
+        return Vec::new();
+    };
+
+    (1..=PULSE_STEPS)
+        .map(|step| {
+            let amount = intensity.clamp(0.0, 1.0) * step as f32 / PULSE_STEPS as f32;
+            let mut flashed = image.to_rgba8();
+            for pixel in flashed.pixels_mut() {
+                for channel in 0..3 {
+                    let value = pixel[channel] as f32;
+                    pixel[channel] = (value + (255.0 - value) * amount) as u8;
+                }
+            }
+            premultiply_alpha(&DynamicImage::ImageRgba8(flashed))
+        })
+        .collect()
+}
+
+/// Colors the image with `tint` (channels 0.0..=1.0), keeping each pixel's
+/// lightness so the icon's shading stays recognizable.
+fn tint_image(image: &DynamicImage, tint: [f32; 3]) -> DynamicImage {
+    let mut pixels = image.to_rgba8();
+    for pixel in pixels.pixels_mut() {
+        let [r, g, b, _] = pixel.0.map(|channel| channel as f32);
+        let luma = 0.299 * r + 0.587 * g + 0.114 * b;
+
+        for channel in 0..3 {
+            pixel[channel] = (luma * tint[channel]).clamp(0.0, 255.0) as u8;
+        }
+    }
+
+    DynamicImage::ImageRgba8(pixels)
+}
+
+/// Hue in degrees, saturation and value in 0.0..=1.0.
+fn rgb_to_hsv(rgb: [u8; 3]) -> (f32, f32, f32) {
+    let [r, g, b] = rgb.map(|channel| channel as f32 / 255.0);
+    let max = r.max(g).max(b);
+    let delta = max - r.min(g).min(b);
+
+    let hue = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    let saturation = if max == 0.0 { 0.0 } else { delta / max };
+
+    (hue, saturation, max)
+}
+
+fn hsv_to_rgb(hue: f32, saturation: f32, value: f32) -> [f32; 3] {
+    let chroma = value * saturation;
+    let sector = hue.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+    let (r, g, b) = match sector as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = value - chroma;
+
+    [r + m, g + m, b + m]
+}
+
+/// Scales each pixel's distance from its own luma: 0.0 gives grayscale, 1.0
+/// leaves the image unchanged and larger values make it more vivid.
+fn adjust_saturation(image: &DynamicImage, factor: f32) -> DynamicImage {
+    if factor == 1.0 {
+        return image.clone();
+    }
+
+    let mut pixels = image.to_rgba8();
+    for pixel in pixels.pixels_mut() {
+        let [r, g, b, _] = pixel.0.map(|channel| channel as f32);
+        let luma = 0.299 * r + 0.587 * g + 0.114 * b;
+
+        for channel in 0..3 {
+            let value = luma + (pixel[channel] as f32 - luma) * factor;
+            pixel[channel] = value.clamp(0.0, 255.0) as u8;
+        }
+    }
+
+    DynamicImage::ImageRgba8(pixels)
+}
+
+/// Blends a color whose alpha is already multiplied in.
+const PREMULTIPLIED_BLEND: wgpu::BlendComponent = wgpu::BlendComponent {
+    src_factor: wgpu::BlendFactor::One,
+    dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+    operation: wgpu::BlendOperation::Add,
+};
+
+/// Multiplies each pixel's color by its alpha, so that filtering between a
+/// logo's edge and its transparent surroundings doesn't pull in the color of
+/// the invisible pixels, which is usually black. Done in linear light, as the
+/// sRGB texture is sampled and blended in.
+fn premultiply_alpha(image: &DynamicImage) -> DynamicImage {
+    let to_linear: Vec<f32> = (0..=255u8)
+        .map(|channel| {
+            let c = channel as f32 / 255.0;
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        })
+        .collect();
+    let to_srgb = |c: f32| {
+        let c = if c <= 0.0031308 {
+            c * 12.92
+        } else {
+            1.055 * c.powf(1.0 / 2.4) - 0.055
+        };
+        (c * 255.0).round() as u8
+    };
+
+    let mut pixels = image.to_rgba8();
+    for pixel in pixels.pixels_mut() {
+        let alpha = pixel[3];
+        if alpha == 255 {
+            continue;
+        }
+        for channel in 0..3 {
+            pixel[channel] = if alpha == 0 {
+                0
+            } else {
+                to_srgb(to_linear[pixel[channel] as usize] * alpha as f32 / 255.0)
+            };
+        }
+    }
+
+    DynamicImage::ImageRgba8(pixels)
+}
+
+fn apply_opacity(image: DynamicImage, opacity: f32) -> DynamicImage {
+    if opacity >= 1.0 {
+        return image;
+    }
+
+    let mut pixels = image.into_rgba8();
+    for pixel in pixels.pixels_mut() {
+        pixel[3] = (pixel[3] as f32 * opacity) as u8;
+    }
+
+    DynamicImage::ImageRgba8(pixels)
+}
+
+/// The mean color of the visible pixels, weighted by alpha.
+fn average_color(image: &DynamicImage) -> Srgb<u8> {
+    let mut sum = [0.0f64; 3];
+    let mut weight = 0.0f64;
+
+    for (_, _, pixel) in image.pixels() {
+        let alpha = pixel[3] as f64;
+        sum[0] += pixel[0] as f64 * alpha;
+        sum[1] += pixel[1] as f64 * alpha;
+        sum[2] += pixel[2] as f64 * alpha;
+        weight += alpha;
+    }
+
+    if weight == 0.0 {
+        return rgb8(255, 255, 255);
+    }
+
+    rgb8(
+        (sum[0] / weight) as u8,
+        (sum[1] / weight) as u8,
+        (sum[2] / weight) as u8,
+    )
+}
+
+fn model(app: &App) -> Model {
+    let sources = ModelSources::load();
+    let config = &sources.config;
+
+    // Resolve the icon before the fullscreen window exists so an error dialog
+    // isn't hidden behind it.
+    let screen_size = primary_screen_size(app);
+    let mut icon = config.icon_source();
+    let loaded_image = match get_image_data(&icon, config.icon_target_size(screen_size, 1.0)) {
+        Ok(img) => img,
+        Err(error) => {
+            log_message!("Icon loading failed: {}, using default icon", error);
+            show_image_error_dialog(&error);
+
+            icon = IconSource::default();
+            let data = include_bytes!("../assets/dvd_logo.png");
+            image::load_from_memory(data).expect("Unable to load default icon")
+        }
+    };
+
+    let windowed = WINDOWED_MODE.load(Ordering::SeqCst);
+
+    let mut window_builder = app
+        .new_window()
+        .event(window_event)
+        .raw_event(raw_model_event)
+        .view(view)
+        .msaa_samples(config.sample_count());
+
+    // Borderless rather than exclusive fullscreen, which would change the
+    // display mode and which Wayland doesn't support at all. On Wayland the
+    // compositor picks the output, as there is no primary monitor to ask for.
+    window_builder = if windowed {
+        window_builder.size(1280, 720).title("DVD Screensaver")
+    } else {
+        window_builder.fullscreen()
+    };
+
+    let _primary_window_id = build_window(app, window_builder);
+
+    let primary_window = app.window(_primary_window_id).unwrap();
+    // Wayland only hides the cursor while it's over the window, which the
+    // fullscreen window always is.
+    primary_window.set_cursor_visible(windowed || !config.hide_cursor);
+
+    // Movement is already scaled by the real time between updates, so only
+    // how often they happen changes.
+    if config.normalize_speed {
+        let rates: Vec<Option<u32>> = app
+            .available_monitors()
+            .into_iter()
+            .map(|monitor| {
+                let rate = monitor.refresh_rate_millihertz();
+                log_message!(
+                    "Monitor {} refreshes at {} Hz",
+                    monitor.name().unwrap_or_default(),
+                    rate.map_or("an unknown rate".to_string(), |rate| {
+                        format!("{:.1}", rate as f32 / 1000.0)
+                    })
+                );
+                rate
+            })
+            .collect();
+        app.set_loop_mode(match refresh_update_interval(rates) {
+            Some(update_interval) => nannou::LoopMode::Rate { update_interval },
+            None => nannou::LoopMode::RefreshSync,
+        });
+    }
+
+    // A Wayland window only gets its fullscreen size once the compositor
+    // configures it, after this, so size the icon for the screen instead.
+    let window_rect = app.window_rect();
+    let icon_area = if is_wayland() && !windowed {
+        primary_screen_size(app)
+    } else {
+        window_rect.wh()
+    };
+    let (target_width, target_height) =
+        config.icon_target_size(icon_area, 1.0 / primary_window.scale_factor());
+    let background = load_background(
+        app,
+        config,
+        window_rect.wh() * primary_window.scale_factor(),
+    );
+
+    Model::new(
+        sources,
+        (icon, loaded_image),
+        ModelView {
+            win: window_rect,
+            icon_target: (target_width, target_height),
+            scale: 1.0,
+            background,
+            is_preview: false,
+            windowed,
+            preview_parent: None,
+        },
+    )
+}
+
+/// The effective config with the RNG seeded and the images it names
+/// resolved, ready for `Model::new`.
+struct ModelSources {
+    config: ScreenSaverConfig,
+    varied_folder: Option<PathBuf>,
+    watched: Option<(PathBuf, Option<(PathBuf, SystemTime)>)>,
+    cycle_images: Option<Vec<IconSource>>,
+}
+
+impl ModelSources {
+    fn load() -> Self {
+        let mut config = load_effective_config();
+        seed_rng(config.seed);
+        IMAGE_CACHE.lock().unwrap().reset(config.image_cache_mb);
+        let varied_folder = varied_logo_folder(&config);
+        let watched = resolve_watched_folder(&mut config);
+        let cycle_images = resolve_cycle_images(&mut config);
+        randomize_launch(&mut config);
+
+        ModelSources {
+            config,
+            varied_folder,
+            watched,
+            cycle_images,
+        }
+    }
+}
+
+/// Where a model is shown, which is all that differs between the screensaver
+/// and its previews.
+struct ModelView {
+    win: Rect,
+    icon_target: (u32, u32),
+    /// Sizes and distances relative to the full screen, below 1 in previews.
+    scale: f32,
+    background: Option<Background>,
+    /// Previews don't write stats or render at a lower internal resolution.
+    is_preview: bool,
+    windowed: bool,
+    preview_parent: Option<isize>,
+}
+
+impl Model {
+    /// Builds the model shown in `view`, with the icon already loaded from
+    /// `icon` and scaled down here to the view's icon size.
+    fn new(
+        sources: ModelSources,
+        (icon, loaded_image): (IconSource, DynamicImage),
+        view: ModelView,
+    ) -> Model {
+        let ModelSources {
+            config,
+            varied_folder,
+            watched,
+            cycle_images,
+        } = sources;
+        let (target_width, target_height) = view.icon_target;
+        let original_image = scale_image(
+            &loaded_image,
+            target_width,
+            target_height,
+            config.scale_filter,
+        );
+
+        let watched_folder = watched.map(|(folder, current)| WatchedFolder {
+            folder,
+            current,
+            since_poll: 0.0,
+            target_size: view.icon_target,
+        });
+        let image_cycler =
+            cycle_images.and_then(|icons| ImageCycler::new(&icons, &config, view.icon_target));
+
+        let images: Vec<LogoImage> = std::iter::once((icon, original_image))
+            .chain(load_varied_images(
+                &config,
+                varied_folder.as_deref(),
+                view.icon_target,
+            ))
+            .map(|(icon, image)| LogoImage::new(&config, icon, image, view.scale))
+            .collect();
+        let logos = spawn_logos(
+            &config,
+            &images,
+            region_in(view.win, config.bounce_region()),
+            view.scale,
+            0..initial_logo_count(&config),
+        );
+
+        Model {
+            images,
+            icon_target: view.icon_target,
+            logos,
+            spawn_interval: (config.spawn_interval_secs > 0.0)
+                .then_some(config.spawn_interval_secs),
+            max_logos: config.max_logo_count(),
+            since_spawn: 0.0,
+            logo_collision: config.logo_collision,
+            background: view.background,
+            bounce_count: 0,
+            corner_hits: 0,
+            since_corner: 0.0,
+            longest_without_corner: 0.0,
+            show_bounce_counter: config.show_bounce_counter,
+            milestone_effects: config.milestone_effects,
+            milestone_flash: 0.0,
+            corner_celebration: config.corner_celebration,
+            confetti: Vec::new(),
+            corner_toast: 0.0,
+            flip_on_bounce: config.flip_on_bounce,
+            squash_intensity: config.squash.then_some(config.squash_intensity),
+            echo_count: if config.echoes { config.echo_count } else { 0 },
+            echo_fade: config.echo_fade_secs.max(0.1),
+            corner_freeze: config.freeze_on_corner.then_some(config.freeze_secs),
+            stats_json: if view.is_preview {
+                None
+            } else {
+                StatsJson::from_args(&config)
+            },
+            recolor_cooldown: config.recolor_cooldown_secs,
+            attract_mode: config.attract_mode,
+            since_strobe: 0.0,
+            night: NightMode::from_config(&config),
+            dim: 1.0,
+            startup_fade: config.startup_fade_secs,
+            pixel_shift: config
+                .pixel_shift
+                .then_some((config.pixel_shift_amount, config.pixel_shift_period_secs)),
+            watched_folder,
+            image_cycler,
+            glow_intensity: config.glow.then_some(config.glow_intensity),
+            scale_filter: config.scale_filter,
+            wall_margin: config.wall_margin * view.scale,
+            region: config.bounce_region(),
+            region_border: config.region_border,
+            obstacle: config.obstacle_rect(),
+            gravity_well: config.gravity_well(),
+            photo_frame: PhotoFrame::new(&config),
+            banner: (!config.banner_text.is_empty()).then(|| {
+                (
+                    config.banner_text.clone(),
+                    config.banner_color,
+                    config.banner_position,
+                )
+            }),
+            walls: config.walls,
+            max_runtime: config.max_runtime_secs.map(|secs| secs as f32),
+            easing_strength: config.easing.then_some(config.easing_strength),
+            recolor: RecolorSettings::from_config(&config),
+            internal_height: config.internal_height.filter(|_| !view.is_preview),
+            offscreen: RefCell::new(None),
+            m_pos: None,
+            mouse_travel: 0.0,
+            mouse_travel_since: 0.0,
+            mouse_exit_distance: config.mouse_exit_distance,
+            exit_only_on_escape: config.exit_only_on_escape,
+            exit_animation: config.exit_animation,
+            exiting: None,
+            is_preview: view.is_preview,
+            windowed: view.windowed,
+            paused: false,
+            occluded: false,
+            minimized: false,
+            preview_parent: view.preview_parent,
+            preview_child: None,
+            image_config: config,
+            image_scale: view.scale,
+        }
+    }
+
+    /// The rect logos bounce inside, in a window of `win`.
+    fn bounds(&self, win: Rect) -> Rect {
+        bounds_with_margin(region_in(win, self.region), self.wall_margin)
+    }
+
+    /// Swaps in a new image, loaded from `icon`, for every logo. Varied logos
+    /// all switch to the one image.
+    fn replace_image(&mut self, (icon, image): (IconSource, DynamicImage), win: Rect) {
+        self.images = vec![LogoImage::new(
+            &self.image_config,
+            icon,
+            image,
+            self.image_scale,
+        )];
+        for logo in &mut self.logos {
+            logo.source = 0;
+        }
+        self.refit_logos(win);
+        if let Some(photo) = &mut self.photo_frame {
+            photo.texture = None;
+        }
+    }
+
+    /// Reloads every image to fit `target`, for when the window's scale factor
+    /// changes the size a fixed pixel size takes up.
+    fn resize_images(&mut self, target: (u32, u32), win: Rect) {
+        log_message!("Resizing logo images to {}x{}", target.0, target.1);
+        self.icon_target = target;
+        if let Some(watched) = &mut self.watched_folder {
+            watched.target_size = target;
+        }
+        if let Some(cycler) = &mut self.image_cycler {
+            cycler.resize(target, self.scale_filter);
+        }
+
+        for image in &mut self.images {
+            match load_icon(&image.icon, target, self.scale_filter) {
+                Ok(original) => {
+                    let icon = std::mem::take(&mut image.icon);
+                    *image = LogoImage::new(&self.image_config, icon, original, self.image_scale);
+                }
+                Err(error) => log_message!("Unable to reload logo image: {}", error),
+            }
+        }
+        self.refit_logos(win);
+        if let Some(photo) = &mut self.photo_frame {
+            photo.texture = None;
+        }
+    }
+
+    /// Fits each logo to its image after the images have changed, keeping its
+    /// position and velocity.
+    fn refit_logos(&mut self, win: Rect) {
+        for logo in &mut self.logos {
+            let visible = self.images[logo.source].visible;
+            let rect = Rect::from_xy_wh(logo.rect.xy(), visible.wh());
+            logo.rect = clamp_rect_inside(rect, win);
+            logo.visible = visible;
+            logo.recolor(&self.images, &self.recolor);
+        }
+    }
+}
+
+impl LogoImage {
+    fn new(
+        config: &ScreenSaverConfig,
+        icon: IconSource,
+        original: DynamicImage,
+        scale: f32,
+    ) -> Self {
+        let (width, height) = original.dimensions();
+        let rainbow = rainbow_frames(config, &icon, &original);
+        LogoImage {
+            icon,
+            visible: if config.tight_collision {
+                visible_bounds(&original)
+            } else {
+                Rect::from_w_h(width as f32, height as f32)
+            },
+            shadow: create_shadow(config, &original, scale),
+            outline: create_outline(config, &original, scale),
+            neon_glow: create_neon_glow(config, &original, scale),
+            rainbow,
+            original,
+        }
+    }
+}
+
+/// The cached hues for `ColorMode::RainbowCycle`, or none in other modes.
+fn rainbow_frames(
+    config: &ScreenSaverConfig,
+    icon: &IconSource,
+    original: &DynamicImage,
+) -> Vec<RainbowFrame> {
+    if !matches!(config.color_mode, ColorMode::RainbowCycle { .. }) {
+        return Vec::new();
+    }
+
+    let settings = RecolorSettings::from_config(config);
+    (0..RAINBOW_FRAMES)
+        .map(|i| {
+            let hue = (i * 360 / RAINBOW_FRAMES) as i32;
+            let image = cached_color_image(original, icon, &settings, hue);
+            RainbowFrame {
+                premultiplied: premultiply_alpha(&image),
+                color: average_color(&image),
+                image,
+            }
+        })
+        .collect()
+}
+
+/// Pixels at least this opaque count as visible for `tight_collision`, so a
+/// faint glow or anti-aliasing fringe doesn't.
+const VISIBLE_ALPHA: u8 = 16;
+
+/// The bounding box of `image`'s visible pixels, relative to its center, or the
+/// whole image if none are.
+fn visible_bounds(image: &DynamicImage) -> Rect {
+    let (width, height) = image.dimensions();
+    let image = image.to_rgba8();
+    let mut bounds: Option<(u32, u32, u32, u32)> = None;
+    for (x, y, pixel) in image.enumerate_pixels() {
+        if pixel[3] < VISIBLE_ALPHA {
+            continue;
+        }
+        bounds = Some(match bounds {
+            Some((left, top, right, bottom)) => {
+                (left.min(x), top.min(y), right.max(x), bottom.max(y))
+            }
+            None => (x, y, x, y),
+        });
+    }
+
+    let Some((left, top, right, bottom)) = bounds else {
+        return Rect::from_w_h(width as f32, height as f32);
+    };
+    // Image rows run downwards, but y runs upwards.
+    let (left, right) = (left as f32, (right + 1) as f32);
+    let (top, bottom) = (
+        height as f32 - top as f32,
+        height as f32 - (bottom + 1) as f32,
+    );
+    Rect::from_x_y_w_h(
+        (left + right - width as f32) / 2.0,
+        (top + bottom - height as f32) / 2.0,
+        right - left,
+        top - bottom,
+    )
+}
+
+/// Applies `varied_logos`: the images the other logos cycle through after the
+/// configured one. These are the rest of the icon set, if it's used, the other
+/// images in the custom icon's folder, if `folder` is set, and otherwise the
+/// built-in icons not already in use.
+fn load_varied_images(
+    config: &ScreenSaverConfig,
+    folder: Option<&Path>,
+    target_size: (u32, u32),
+) -> Vec<(IconSource, DynamicImage)> {
+    if !config.varied_logos || config.max_logo_count() < 2 {
+        return Vec::new();
+    }
+
+    let icons: Vec<IconSource> = match folder {
+        _ if config.uses_icon_set() => config
+            .icon_set
+            .iter()
+            .skip(1)
+            .take(config.max_logo_count() - 1)
+            .cloned()
+            .collect(),
+        Some(folder) => {
+            let mut paths: Vec<PathBuf> = std::fs::read_dir(folder)
+                .map(|entries| {
+                    entries
+                        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                        .filter(|path| is_supported_image(path))
+                        .filter(|path| path != Path::new(&config.custom_image_path))
+                        .collect()
+                })
+                .unwrap_or_default();
+            paths.sort();
+            paths
+                .iter()
+                .take(config.max_logo_count() - 1)
+                .map(|path| IconSource::Custom(path.clone()))
+                .collect()
+        }
+        None => (0..BUILT_IN_ICON_COUNT)
+            .filter(|&index| index != config.image_index)
+            .map(IconSource::BuiltIn)
+            .collect(),
+    };
+
+    icons
+        .into_iter()
+        .filter_map(
+            |icon| match load_icon(&icon, target_size, config.scale_filter) {
+                Ok(image) => Some((icon, image)),
+                Err(error) => {
+                    log_message!("Skipping varied logo image: {}", error);
+                    None
+                }
+            },
+        )
+        .collect()
+}
+
+/// The custom icon's folder, if `varied_logos` should draw from it. Taken
+/// before the icon path is narrowed to a single image of the folder.
+fn varied_logo_folder(config: &ScreenSaverConfig) -> Option<PathBuf> {
+    let folder = PathBuf::from(&config.custom_image_path);
+    (config.varied_logos && config.image_index == BUILT_IN_ICON_COUNT && folder.is_dir())
+        .then_some(folder)
+}
+
+fn initial_logo_count(config: &ScreenSaverConfig) -> usize {
+    if config.spawn_interval_secs > 0.0 {
+        1
+    } else {
+        config.logo_count.max(1)
+    }
+}
+
+/// Creates the logos numbered `indices`. The first logo starts at the
+/// configured start position; the rest start at random positions and
+/// directions. Each logo's speed is the base speed varied by up to
+/// `speed_variance` percent, fixed for its lifetime. Logos take turns through
+/// `images`.
+fn spawn_logos(
+    config: &ScreenSaverConfig,
+    images: &[LogoImage],
+    win: Rect,
+    speed_scale: f32,
+    indices: std::ops::Range<usize>,
+) -> Vec<Logo> {
+    let variance = config.speed_variance.clamp(0.0, 100.0) / 100.0;
+    let bounds = bounds_with_margin(win, config.wall_margin * speed_scale);
+
+    let mut rng = RNG.lock().unwrap();
+    let starts: Vec<(Vec2, Vec2)> = indices
+        .clone()
+        .map(|i| {
+            let size = images[i % images.len()].visible.wh();
+            // How far the logo's center can be from the middle while fully inside.
+            let range = ((bounds.wh() - size) / 2.0).max(Vec2::ZERO);
+
+            let speed_factor = if variance > 0.0 {
+                1.0 + rng.gen_range(-variance..=variance)
+            } else {
+                1.0
+            };
+            let speed = config.axis_speeds() * speed_scale * speed_factor;
+
+            // Only the first logo honors the start position; the rest are
+            // scattered so they don't all overlap.
+            let start_position = if i == 0 {
+                config.start_position
+            } else {
+                StartPosition::Random
+            };
+
+            let (position, direction) = match start_position.corner() {
+                Some(corner) => (bounds.xy() + corner * range, -corner),
+                None if start_position == StartPosition::Center => (bounds.xy(), Vec2::ONE),
+                None => {
+                    let position = Vec2::new(
+                        bounds.x() + rng.gen_range(-range.x..=range.x),
+                        bounds.y() + rng.gen_range(-range.y..=range.y),
+                    );
+                    let direction = Vec2::new(
+                        if rng.gen::<bool>() { 1.0 } else { -1.0 },
+                        if rng.gen::<bool>() { 1.0 } else { -1.0 },
+                    );
+                    (position, direction)
+                }
+            };
+
+            (position, config.start_axis.constrain(direction) * speed)
+        })
+        .collect();
+    // `change_color` takes the lock itself.
+    drop(rng);
+
+    let settings = RecolorSettings::from_config(config);
+    indices
+        .zip(starts)
+        .map(|(i, (position, vel))| {
+            let source = i % images.len();
+            let original = &images[source].original;
+            // Later colors are random as usual, starting from this hue.
+            let image = match config.initial_hue {
+                Some(hue) if i == 0 => {
+                    *LAST_HUE.lock().unwrap() = hue;
+                    cached_color_image(original, &images[source].icon, &settings, hue)
+                }
+                _ => change_color(original, &images[source].icon, &settings),
+            };
+            let color = average_color(&image);
+            let visible = images[source].visible;
+            let hue = *LAST_HUE.lock().unwrap() as f32;
+            let pulse_frames = pulse_frames(&image, &settings);
+
+            Logo {
+                source,
+                premultiplied: premultiply_alpha(&image),
+                image,
+                rect: Rect::from_xy_wh(position, visible.wh()),
+                visible,
+                vel,
+                color,
+                glow_timer: 0.0,
+                bounce_tracker: BounceTracker::default(),
+                neon_image: neon_image_for(images[source].neon_glow.as_ref(), color),
+                squash_timer: Vec2::ZERO,
+                squash_side: Vec2::ZERO,
+                echoes: VecDeque::new(),
+                freeze_timer: 0.0,
+                since_recolor: f32::INFINITY,
+                hue,
+                rainbow_frame: None,
+                pulse_timer: 0.0,
+                pulse_frames,
+            }
+        })
+        .collect()
+}
+
+/// Loads the configured background image, downscaled to no more than needed
+/// to fit it to `size` pixels. Errors are logged and leave the background black.
+fn load_background(app: &App, config: &ScreenSaverConfig, size: Vec2) -> Option<Background> {
+    if config.background_image_path.is_empty() {
+        return None;
+    }
+
+    let target = (size.x as u32, size.y as u32);
+    let image = match load_image_safe(&config.background_image_path, target) {
+        Ok(image) => image,
+        Err(error) => {
+            log_message!(
+                "Unable to load background '{}': {}",
+                config.background_image_path,
+                error
+            );
+            return None;
+        }
+    };
+
+    let (width, height) = (target.0.max(1), target.1.max(1));
+    let image = match config.background_fit {
+        // Scaled but not cropped, so `view` can still cover the window if its
+        // aspect ratio changes with the resolution or monitor.
+        BackgroundFit::Cover if image.width() > width && image.height() > height => {
+            let scale =
+                (width as f32 / image.width() as f32).max(height as f32 / image.height() as f32);
+            image.resize_exact(
+                (image.width() as f32 * scale).ceil() as u32,
+                (image.height() as f32 * scale).ceil() as u32,
+                FilterType::Triangle,
+            )
+        }
+        BackgroundFit::Contain if image.width() > width || image.height() > height => {
+            image.resize(width, height, FilterType::Triangle)
+        }
+        _ => image,
+    };
+
+    Some(Background {
+        texture: wgpu::Texture::from_image(app, &image),
+        fit: config.background_fit,
+    })
+}
+
+fn create_shadow(
+    config: &ScreenSaverConfig,
+    original_image: &DynamicImage,
+    scale: f32,
+) -> Option<Shadow> {
+    if !config.shadow {
+        return None;
+    }
+
+    let opacity = config.shadow_opacity.clamp(0.0, 1.0) * config.logo_opacity.clamp(0.0, 1.0);
+    let mut silhouette = original_image.to_rgba8();
+    for pixel in silhouette.pixels_mut() {
+        let alpha = pixel[3] as f32 * opacity;
+        *pixel = image::Rgba([0, 0, 0, alpha as u8]);
+    }
+
+    let offset = config.shadow_offset * scale;
+
+    Some(Shadow {
+        image: DynamicImage::ImageRgba8(silhouette),
+        offset: Vec2::new(offset, -offset),
+    })
+}
+
+fn create_outline(
+    config: &ScreenSaverConfig,
+    original_image: &DynamicImage,
+    scale: f32,
+) -> Option<Outline> {
+    if !config.outline {
+        return None;
+    }
+
+    let [r, g, b] = config.outline_color;
+    let mut silhouette = original_image.to_rgba8();
+    for pixel in silhouette.pixels_mut() {
+        *pixel = image::Rgba([r, g, b, pixel[3]]);
+    }
+
+    Some(Outline {
+        image: DynamicImage::ImageRgba8(silhouette),
+        thickness: (config.outline_thickness * scale).max(0.5),
+    })
+}
+
+/// Blurs the logo's silhouette once per image, padded so the blur isn't
+/// clipped at the image's edges.
+fn create_neon_glow(
+    config: &ScreenSaverConfig,
+    original_image: &DynamicImage,
+    scale: f32,
+) -> Option<NeonGlow> {
+    if !config.neon_glow {
+        return None;
+    }
+
+    let radius = (config.neon_glow_radius * scale).max(1.0);
+    let padding = (radius * 2.0).ceil() as u32;
+    let (width, height) = original_image.dimensions();
+    let mut mask = image::GrayImage::new(width + 2 * padding, height + 2 * padding);
+    for (x, y, pixel) in original_image.to_rgba8().enumerate_pixels() {
+        mask.put_pixel(x + padding, y + padding, image::Luma([pixel[3]]));
+    }
+    let mask = image::imageops::blur(&mask, radius / 2.0);
+
+    let fixed = (!config.neon_glow_follows_logo).then(|| tint_mask(&mask, config.neon_glow_color));
+
+    Some(NeonGlow {
+        mask,
+        padding: padding as f32,
+        fixed,
+    })
+}
+
+/// The glow colored for a logo, if the glow follows the logo's color.
+fn neon_image_for(neon_glow: Option<&NeonGlow>, color: Srgb<u8>) -> Option<DynamicImage> {
+    neon_glow
+        .filter(|neon_glow| neon_glow.fixed.is_none())
+        .map(|neon_glow| tint_mask(&neon_glow.mask, [color.red, color.green, color.blue]))
+}
+
+fn tint_mask(mask: &image::GrayImage, [r, g, b]: [u8; 3]) -> DynamicImage {
+    DynamicImage::ImageRgba8(image::RgbaImage::from_fn(
+        mask.width(),
+        mask.height(),
+        |x, y| {
+            // Boosted, as blurring spreads the silhouette's opacity thin.
+            let alpha = (mask.get_pixel(x, y)[0] as f32 * 1.5).min(255.0);
+            image::Rgba([r, g, b, alpha as u8])
+        },
+    ))
+}
+
+/// Whether winit will use Wayland, which it prefers when both it and X11 are
+/// available unless told otherwise.
+fn is_wayland() -> bool {
+    cfg!(target_os = "linux")
+        && std::env::var_os("WAYLAND_DISPLAY").is_some()
+        && std::env::var("WINIT_UNIX_BACKEND").map_or(true, |backend| backend == "wayland")
+}
+
+/// Tracks whether the window is minimized or covered, which nannou's own
+/// events don't report, so `update` and `view` can skip their work meanwhile.
+fn raw_model_event(_app: &App, model: &mut Model, event: &nannou::winit::event::WindowEvent) {
+    match event {
+        nannou::winit::event::WindowEvent::Occluded(occluded) => model.occluded = *occluded,
+        nannou::winit::event::WindowEvent::Resized(size) => {
+            model.minimized = size.width == 0 || size.height == 0;
+        }
+        _ => (),
+    }
+}
+
+fn window_event(app: &App, model: &mut Model, event: WindowEvent) {
+    // Mouse positions are relative to the window's center, so the same pointer
+    // position reads differently after a resize. Wayland also only reports
+    // the pointer while it's over the window, and resizes the window to
+    // fullscreen after it opens. Start measuring travel afresh after either
+    // rather than mistake the jump for movement.
+    if let WindowEvent::Resized(_) | WindowEvent::MouseEntered | WindowEvent::MouseExited = event {
+        model.m_pos = None;
+    }
+
+    if let WindowEvent::Resized(_) = event {
+        if model.minimized {
+            return;
+        }
+        let win = model.bounds(app.window_rect());
+
+        // A fixed pixel size takes up a different number of points once the
+        // scale factor changes, such as on a move to another monitor.
+        if !model.is_preview {
+            if let SizeMode::Pixels(_) = model.image_config.size_mode {
+                let target = model.image_config.icon_target_size(
+                    app.window_rect().wh(),
+                    1.0 / app.main_window().scale_factor(),
+                );
+                if target != model.icon_target {
+                    model.resize_images(target, win);
+                }
+            }
+        }
+
+        for logo in &mut model.logos {
+            logo.rect = clamp_rect_inside(logo.rect, win);
+        }
+    }
+
+    if model.is_preview {
+        return;
+    }
+
+    // The windowed dev mode (`/w`) pauses on space and takes a screenshot on
+    // F12 instead of quitting, and ignores the mouse, which is expected to
+    // move over a normal window.
+    if model.windowed {
+        match event {
+            WindowEvent::KeyPressed(Key::Space) => model.paused = !model.paused,
+            WindowEvent::KeyPressed(Key::F12) => {
+                let path = screenshot_path();
+                log_message!("Saving screenshot to {}", path.display());
+                app.main_window().capture_frame(path);
+            }
+            WindowEvent::MousePressed(..) | WindowEvent::KeyPressed(..) => request_exit(app, model),
+            _ => (),
+        }
+        return;
+    }
+
+    if model.exit_only_on_escape {
+        if let WindowEvent::KeyPressed(Key::Escape) = event {
+            request_exit(app, model);
+        }
+        return;
+    }
+
+    if app.time > 0.1 {
+        match event {
+            WindowEvent::MouseMoved(pos) => {
+                if let Some(last) = model.m_pos {
+                    if app.time - model.mouse_travel_since > MOUSE_EXIT_WINDOW {
+                        model.mouse_travel = 0.0;
+                        model.mouse_travel_since = app.time;
+                    }
+                    model.mouse_travel += last.distance(pos);
+                    if model.mouse_travel > model.mouse_exit_distance {
+                        request_exit(app, model);
+                    }
+                }
+                model.m_pos = Some(pos);
+            }
+            WindowEvent::MousePressed(..)
+            | WindowEvent::KeyPressed(..)
+            | WindowEvent::MouseWheel(..) => request_exit(app, model),
+            _ => (),
+        }
+    }
+}
+
+/// How long, in seconds, the exit animation takes.
+const EXIT_DURATION: f32 = 0.4;
+
+/// Quits, or starts the exit animation if it's enabled. Input during the
+/// animation quits at once, so dismissing never has to wait.
+fn request_exit(app: &App, model: &mut Model) {
+    if !model.exit_animation || model.exiting.is_some() {
+        app.quit();
+        return;
+    }
+
+    model.exiting = Some(0.0);
+    // Skipping it takes a deliberate movement, not the rest of this one.
+    model.mouse_travel = 0.0;
+    model.mouse_travel_since = app.time;
+}
+
+/// The time, in seconds, over which mouse travel adds up towards exiting.
+const MOUSE_EXIT_WINDOW: f32 = 1.0;
+
+/// Internal resolutions offered in the config dialog.
+const INTERNAL_HEIGHTS: [u32; 3] = [1440, 1080, 720];
+
+/// The scene rendered at a lower internal resolution, to be upscaled to the
+/// window.
+struct Offscreen {
+    texture: wgpu::Texture,
+    renderer: nannou::draw::Renderer,
+    reshaper: wgpu::TextureReshaper,
+}
+
+impl Offscreen {
+    fn new(device: &wgpu::Device, size: [u32; 2], frame_samples: u32) -> Self {
+        let texture = wgpu::TextureBuilder::new()
+            .size(size)
+            .usage(wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING)
+            .sample_count(1)
+            .format(Frame::TEXTURE_FORMAT)
+            .build(device);
+        let renderer = nannou::draw::RendererBuilder::new()
+            .build_from_texture_descriptor(device, texture.descriptor());
+        let reshaper = wgpu::TextureReshaper::new(
+            device,
+            &texture.view().build(),
+            1,
+            texture.sample_type(),
+            frame_samples,
+            Frame::TEXTURE_FORMAT,
+        );
+
+        Offscreen {
+            texture,
+            renderer,
+            reshaper,
+        }
+    }
+}
+
+/// The size to render at for `internal_height`, keeping the frame's aspect
+/// ratio, or `None` if that wouldn't be smaller than the frame.
+fn internal_size(frame_size: [u32; 2], internal_height: Option<u32>) -> Option<[u32; 2]> {
+    let height = internal_height.filter(|&height| height > 0 && height < frame_size[1])?;
+    let width = (frame_size[0] as u64 * height as u64 / frame_size[1] as u64).max(1) as u32;
+    Some([width, height])
+}
+
+/// A timestamped PNG path in the user's Pictures folder, falling back to the
+/// config folder.
+fn screenshot_path() -> PathBuf {
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_millis())
+        .unwrap_or(0);
+
+    dirs::picture_dir()
+        .or_else(|| get_config_path().parent().map(Path::to_path_buf))
+        .unwrap_or_default()
+        .join(format!("dvd_screensaver_{}.png", timestamp))
+}
+
+fn clamp_rect_inside(rect: Rect, win: Rect) -> Rect {
+    let half_w = ((win.w() - rect.w()) / 2.0).max(0.0);
+    let half_h = ((win.h() - rect.h()) / 2.0).max(0.0);
+    let x = rect.x().clamp(win.x() - half_w, win.x() + half_w);
+    let y = rect.y().clamp(win.y() - half_h, win.y() + half_h);
+
+    Rect::from_x_y_w_h(x, y, rect.w(), rect.h())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Edge {
+    Left,
+    Right,
+    Bottom,
+    Top,
+}
+
+impl Edge {
+    const ALL: [Edge; 4] = [Edge::Left, Edge::Right, Edge::Bottom, Edge::Top];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Edge::Left => "left",
+            Edge::Right => "right",
+            Edge::Bottom => "bottom",
+            Edge::Top => "top",
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Edge::Left => "Left",
+            Edge::Right => "Right",
+            Edge::Bottom => "Bottom",
+            Edge::Top => "Top",
+        }
+    }
+
+    /// The axis this edge is perpendicular to (0 for x, 1 for y) and the
+    /// direction that points out of the window through it.
+    fn axis(&self) -> (usize, f32) {
+        match self {
+            Edge::Left => (0, -1.0),
+            Edge::Right => (0, 1.0),
+            Edge::Bottom => (1, -1.0),
+            Edge::Top => (1, 1.0),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct BounceFlags {
+    left: bool,
+    right: bool,
+    bottom: bool,
+    top: bool,
+    obstacle: bool,
+    /// The logo left through an open wall and was respawned.
+    exited: bool,
+}
+
+impl BounceFlags {
+    fn any(&self) -> bool {
+        self.left || self.right || self.bottom || self.top || self.obstacle
+    }
+
+    fn set(&mut self, edge: Edge) {
+        match edge {
+            Edge::Left => self.left = true,
+            Edge::Right => self.right = true,
+            Edge::Bottom => self.bottom = true,
+            Edge::Top => self.top = true,
+        }
+    }
+}
+
+/// Upper bound on a logo's speed in points per second, whatever the speed
+/// settings multiply out to.
+const MAX_LOGO_SPEED: f32 = 5000.0;
+
+/// Two entry times closer than this count as hitting a corner of the obstacle.
+const OBSTACLE_CORNER_TOLERANCE: f32 = 1e-3;
+
+/// Reflects a logo of half size `half` moving from `from` to `to` off
+/// `obstacle`, or returns `None` if it doesn't touch it. The whole path is
+/// tested, so a fast logo can't pass through. Hitting a corner reflects on
+/// both axes, and a logo that starts inside is pushed out the nearest side.
+fn bounce_off_obstacle(
+    from: Vec2,
+    to: Vec2,
+    vel: Vec2,
+    half: Vec2,
+    obstacle: Rect,
+) -> Option<(Vec2, Vec2)> {
+    // Where the logo's center can't go.
+    let min = obstacle.bottom_left() - half;
+    let max = obstacle.top_right() + half;
+    let path = to - from;
+
+    let mut entry = Vec2::splat(f32::NEG_INFINITY);
+    let mut exit = Vec2::splat(f32::INFINITY);
+    for axis in 0..2 {
+        if path[axis] == 0.0 {
+            if from[axis] <= min[axis] || from[axis] >= max[axis] {
+                return None;
+            }
+        } else {
+            let t1 = (min[axis] - from[axis]) / path[axis];
+            let t2 = (max[axis] - from[axis]) / path[axis];
+            entry[axis] = t1.min(t2);
+            exit[axis] = t1.max(t2);
+        }
+    }
+
+    let t_entry = entry.max_element();
+    let t_exit = exit.min_element();
+    if t_entry >= t_exit || t_entry > 1.0 || t_exit <= 0.0 {
+        return None;
+    }
+
+    let mut pos = to;
+    let mut vel = vel;
+    if t_entry < 0.0 {
+        // Started inside: leave by the side needing the least movement.
+        let to_min = to - min;
+        let to_max = max - to;
+        let depth = to_min.min(to_max);
+        let axis = if depth.x < depth.y { 0 } else { 1 };
+        let out = if to_min[axis] < to_max[axis] {
+            -1.0
+        } else {
+            1.0
+        };
+        pos[axis] = if out < 0.0 { min[axis] } else { max[axis] };
+        vel[axis] = out * vel[axis].abs();
+        return Some((pos, vel));
+    }
+
+    for axis in 0..2 {
+        if entry[axis] >= t_entry - OBSTACLE_CORNER_TOLERANCE {
+            // Flush with the face that was hit, heading back out.
+            let out = -path[axis].signum();
+            pos[axis] = if out < 0.0 { min[axis] } else { max[axis] };
+            vel[axis] = out * vel[axis].abs();
+        }
+    }
+    Some((pos, vel))
+}
+
+/// Moves `rect` by `vel` over `dt` seconds and applies each wall of `win`
+/// according to `walls`, indexed by `Edge`. Each wall test looks at where the
+/// edge ends up after the whole move, so a wall crossed at any point during
+/// the frame is caught no matter how large `dt` or the speed is; a reflecting
+/// wall then puts the logo back flush with it. The logo bounces off
+/// `obstacle` the same way.
+fn step(
+    rect: Rect,
+    vel: Vec2,
+    win: Rect,
+    dt: f32,
+    walls: &[WallMode; 4],
+    obstacle: Option<Rect>,
+) -> (Rect, Vec2, BounceFlags) {
+    let mut vel = vel.clamp_length_max(MAX_LOGO_SPEED);
+    let mut flags = BounceFlags::default();
+
+    let mut pos = rect.xy() + vel * dt;
+    let half = rect.wh() / 2.0;
+
+    if let Some(obstacle) = obstacle {
+        if let Some((hit_pos, hit_vel)) = bounce_off_obstacle(rect.xy(), pos, vel, half, obstacle) {
+            pos = hit_pos;
+            vel = hit_vel;
+            flags.obstacle = true;
+        }
+    }
+    let win_half = win.wh() / 2.0;
+
+    for edge in Edge::ALL {
+        let (axis, out) = edge.axis();
+        let wall = win.xy()[axis] + out * win_half[axis];
+        let leading = pos[axis] + out * half[axis];
+        let trailing = pos[axis] - out * half[axis];
+
+        match walls[edge as usize] {
+            WallMode::Reflect => {
+                // Only logos heading outwards bounce, so one wrapping in
+                // from beyond this wall can enter.
+                if out * (leading - wall) >= 0.0 && out * vel[axis] >= 0.0 {
+                    pos[axis] = wall - out * half[axis];
+                    vel[axis] = -out * vel[axis].abs();
+                    flags.set(edge);
+                }
+            }
+            WallMode::Wrap => {
+                if out * (trailing - wall) >= 0.0 && out * vel[axis] > 0.0 {
+                    let opposite = win.xy()[axis] - out * win_half[axis];
+                    pos[axis] = opposite - out * half[axis];
+                }
+            }
+            WallMode::Open => {
+                if out * (trailing - wall) >= 0.0 && out * vel[axis] > 0.0 {
+                    pos = win.xy();
+                    flags.exited = true;
+                }
+            }
+        }
+    }
+
+    (Rect::from_xy_wh(pos, rect.wh()), vel, flags)
+}
+
+/// Bounces off the same wall closer together than this, in seconds, count
+/// towards the logo being stuck to it.
+const STUCK_WINDOW: f32 = 0.25;
+const STUCK_BOUNCES: u32 = 3;
+
+/// Notices a logo pinned against a wall, e.g. sliding along it with almost no
+/// velocity away from it, which would otherwise bounce and recolor it every
+/// frame.
+#[derive(Clone, Copy, Debug)]
+struct BounceTracker {
+    /// Indexed by `Edge`.
+    since_bounce: [f32; 4],
+    streak: [u32; 4],
+}
+
+impl Default for BounceTracker {
+    fn default() -> Self {
+        BounceTracker {
+            since_bounce: [f32::INFINITY; 4],
+            streak: [0; 4],
+        }
+    }
+}
+
+/// A horizontal and a vertical wall hit within this many seconds of each
+/// other count as hitting the corner.
+const CORNER_TOLERANCE: f32 = 0.1;
+
+impl BounceTracker {
+    /// Whether the logo has just hit both a horizontal and a vertical wall.
+    fn is_corner(&self) -> bool {
+        let recent = |edge: Edge| self.since_bounce[edge as usize] <= CORNER_TOLERANCE;
+        (recent(Edge::Left) || recent(Edge::Right)) && (recent(Edge::Bottom) || recent(Edge::Top))
+    }
+
+    /// Records the bounces from a `step` and, once a wall has been hit
+    /// `STUCK_BOUNCES` times in quick succession, moves the logo off it and
+    /// makes sure it heads away at a reasonable speed.
+    fn unstick(
+        &mut self,
+        rect: Rect,
+        vel: Vec2,
+        win: Rect,
+        bounces: BounceFlags,
+        dt: f32,
+    ) -> (Rect, Vec2) {
+        let mut pos = rect.xy();
+        let mut vel = vel;
+        let hits = [bounces.left, bounces.right, bounces.bottom, bounces.top];
+
+        for edge in Edge::ALL {
+            let i = edge as usize;
+            self.since_bounce[i] += dt;
+            if !hits[i] {
+                continue;
+            }
+
+            self.streak[i] = if self.since_bounce[i] <= STUCK_WINDOW {
+                self.streak[i] + 1
+            } else {
+                1
+            };
+            self.since_bounce[i] = 0.0;
+
+            if self.streak[i] >= STUCK_BOUNCES {
+                let (axis, out) = edge.axis();
+                let wall = win.xy()[axis] + out * win.wh()[axis] / 2.0;
+                let escape_speed = (vel.length() * 0.25).max(10.0);
+
+                pos[axis] = wall - out * (rect.wh()[axis] / 2.0 + 1.0);
+                if vel[axis].abs() < escape_speed {
+                    vel[axis] = -out * escape_speed;
+                }
+                self.streak[i] = 0;
+            }
+        }
+
+        (Rect::from_xy_wh(pos, rect.wh()), vel)
+    }
+}
+
+/// `region`, given as fractions of `win`, in the same coordinates as `win`.
+fn region_in(win: Rect, region: Rect) -> Rect {
+    Rect::from_xy_wh(win.xy() + region.xy() * win.wh(), region.wh() * win.wh())
+}
+
+/// The area the logos bounce within: the window shrunk by `margin` on every
+/// side, never smaller than a point.
+fn bounds_with_margin(win: Rect, margin: f32) -> Rect {
+    let margin = margin.clamp(0.0, (win.w().min(win.h()) / 2.0 - 1.0).max(0.0));
+    win.pad(margin)
+}
+
+/// Adds this run's corner hits to the persisted stats, and its longest stretch
+/// without one if that's a new record.
+fn exit(_app: &App, model: Model) {
+    if model.is_preview {
+        return;
+    }
+
+    let mut stats = load_stats();
+    stats.corner_hits += model.corner_hits;
+    stats.longest_without_corner_secs = stats
+        .longest_without_corner_secs
+        .max(model.longest_without_corner)
+        .max(model.since_corner);
+    save_stats(&stats);
+}
+
+/// The pull is as if from this close, in window heights, at any distance
+/// nearer than that, so it stays gentle right at the well.
+const GRAVITY_MIN_DISTANCE: f32 = 0.1;
+
+/// Turns `vel` towards `well` with a force inversely proportional to the
+/// distance, measured in window heights so the preview curves the same. Only
+/// the direction changes, so the pull can't build up runaway speeds.
+fn gravity_pull(
+    position: Vec2,
+    vel: Vec2,
+    well: Vec2,
+    strength: f32,
+    window_height: f32,
+    delta_time: f32,
+) -> Vec2 {
+    let offset = well - position;
+    let distance = (offset.length() / window_height).max(GRAVITY_MIN_DISTANCE);
+    let accel = offset.normalize_or_zero() * strength * window_height / distance;
+    (vel + accel * delta_time).normalize_or_zero() * vel.length()
+}
+
+/// How much to slow a logo on each axis as it nears a wall, from 1 (full
+/// speed) down to 0.25 right at the wall with `strength` 1. This only changes
+/// how fast the logo moves; bounces still happen at the real edges.
+fn ease_factor(rect: Rect, win: Rect, strength: f32) -> Vec2 {
+    let ease = |distance: f32, size: f32| {
+        let t = (distance / (size * 0.15)).clamp(0.0, 1.0);
+        let smooth = t * t * (3.0 - 2.0 * t);
+        1.0 - strength.clamp(0.0, 1.0) * (1.0 - smooth) * 0.75
+    };
+
+    Vec2::new(
+        ease(
+            (rect.left() - win.left()).min(win.right() - rect.right()),
+            win.w(),
+        ),
+        ease(
+            (rect.bottom() - win.bottom()).min(win.top() - rect.top()),
+            win.h(),
+        ),
+    )
+}
+
+/// The longest time step, in seconds, that `update` will simulate at once.
+const MAX_FRAME_DELTA: f32 = 0.1;
+
+/// The time `update` simulates for a step, clamped so the logos don't jump
+/// after a stall or after being hidden.
+fn frame_delta(since_prev_update: std::time::Duration) -> f32 {
+    since_prev_update.as_secs_f32().min(MAX_FRAME_DELTA)
+}
+
+/// How often to update so the fastest monitor gets a new frame on each of its
+/// refreshes, from each monitor's rate in millihertz if it reports one.
+fn refresh_update_interval(
+    rates: impl IntoIterator<Item = Option<u32>>,
+) -> Option<std::time::Duration> {
+    let fastest = rates.into_iter().flatten().filter(|&rate| rate > 0).max()?;
+    Some(std::time::Duration::from_secs_f64(1000.0 / fastest as f64))
+}
+
+fn update(app: &App, model: &mut Model, _update: Update) {
+    #[cfg(windows)]
+    if let (Some(child), Some(parent)) = (model.preview_child, model.preview_parent) {
+        let _ = sync_preview_size(child, parent);
+    }
+
+    if let Some(exiting) = &mut model.exiting {
+        *exiting += app.duration.since_prev_update.secs() as f32;
+        if *exiting >= EXIT_DURATION {
+            app.quit();
+        }
+        return;
+    }
+
+    if let Some(max_runtime) = model.max_runtime {
+        if !model.is_preview && app.time >= max_runtime {
+            log_message!("Exiting after the maximum runtime of {}s", max_runtime);
+            app.quit();
+            return;
+        }
+    }
+
+    if let Some(night) = &model.night {
+        model.dim = night.dim();
+    }
+
+    // Taken out so `write` can read the rest of the model.
+    if let Some(mut stats_json) = model.stats_json.take() {
+        stats_json.since_write += app.duration.since_prev_update.secs() as f32;
+        if stats_json.since_write >= STATS_JSON_INTERVAL {
+            stats_json.since_write = 0.0;
+            stats_json.write(app, model);
+        }
+        model.stats_json = Some(stats_json);
+    }
+
+    if model.paused || model.occluded || model.minimized {
+        return;
+    }
+
+    let win = model.bounds(app.window_rect());
+    let obstacle = model
+        .obstacle
+        .map(|obstacle| region_in(app.window_rect(), obstacle));
+    let delta_time = frame_delta(app.duration.since_prev_update);
+
+    // Recolored the usual way, so the strobe follows the color mode and
+    // bounces take over seamlessly once it ends.
+    if model.attract_mode && app.time < ATTRACT_DURATION {
+        model.since_strobe += delta_time;
+        if model.since_strobe >= ATTRACT_STROBE_INTERVAL {
+            model.since_strobe = 0.0;
+            for logo in &mut model.logos {
+                logo.recolor(&model.images, &model.recolor);
+                logo.glow_timer = 0.0;
+            }
+        }
+    }
+
+    if let Some(image) = model
+        .watched_folder
+        .as_mut()
+        .and_then(|watched| watched.poll(delta_time, model.scale_filter))
+    {
+        model.replace_image(image, win);
+    }
+
+    model.milestone_flash = (model.milestone_flash - delta_time).max(0.0);
+    model.corner_toast = (model.corner_toast - delta_time).max(0.0);
+    model.confetti.retain_mut(|confetti| {
+        confetti.life -= delta_time;
+        confetti.vel.y -= app.window_rect().h() * delta_time;
+        confetti.xy += confetti.vel * delta_time;
+        confetti.life > 0.0
+    });
+    model.since_corner += delta_time;
+
+    // A photo frame stands still, moving on to the next image after a pan
+    // the way bouncing logos would after a bounce.
+    let mut bounced = false;
+    let photo_frame = model.photo_frame.is_some();
+    if let Some(photo) = &mut model.photo_frame {
+        bounced = photo.advance(delta_time);
+        if let Some(logo) = model.logos.first_mut().filter(|_| bounced && photo.recolor) {
+            logo.recolor(&model.images, &model.recolor);
+            photo.texture = None;
+        }
+        if photo.texture.is_none() {
+            let image = match model.logos.first() {
+                Some(logo) if photo.recolor => &logo.image,
+                _ => &model.images[0].original,
+            };
+            photo.texture = Some(wgpu::Texture::from_image(app, image));
+        }
+    }
+    for logo in model.logos.iter_mut().filter(|_| !photo_frame) {
+        if logo.freeze_timer > 0.0 {
+            logo.freeze_timer -= delta_time;
+            continue;
+        }
+
+        if let Some((well, strength)) = model.gravity_well {
+            let window = app.window_rect();
+            logo.vel = gravity_pull(
+                logo.rect.xy(),
+                logo.vel,
+                window.xy() + well * window.wh(),
+                strength,
+                window.h(),
+                delta_time,
+            );
+        }
+
+        let ease = model
+            .easing_strength
+            .map_or(Vec2::ONE, |strength| ease_factor(logo.rect, win, strength));
+        let (rect, vel, bounces) = step(
+            logo.rect,
+            logo.vel * ease,
+            win,
+            delta_time,
+            &model.walls,
+            obstacle,
+        );
+        let vel = vel / ease;
+        let (rect, vel) = logo
+            .bounce_tracker
+            .unstick(rect, vel, win, bounces, delta_time);
+        logo.rect = rect;
+        logo.vel = vel;
+        logo.glow_timer = (logo.glow_timer - delta_time).max(0.0);
+        logo.since_recolor += delta_time;
+        if let ColorMode::RainbowCycle { speed } = model.recolor.color_mode {
+            logo.hue = (logo.hue + speed * delta_time).rem_euclid(360.0);
+            logo.show_rainbow_frame(&model.images);
+        }
+        logo.squash_timer = (logo.squash_timer - delta_time).max(Vec2::ZERO);
+        if model.squash_intensity.is_some() {
+            logo.squash(bounces);
+        }
+
+        let echo_fade = model.echo_fade;
+        logo.echoes
+            .retain_mut(|echo| echo.fade(delta_time, echo_fade));
+        if model.echo_count > 0 && bounces.any() {
+            let echo = Echo::new(logo, model.flip_on_bounce);
+            logo.echoes.push_back(echo);
+            if logo.echoes.len() > model.echo_count {
+                logo.echoes.pop_front();
+            }
+        }
+
+        logo.pulse_timer = (logo.pulse_timer - delta_time).max(0.0);
+        let cooled_down = logo.since_recolor >= model.recolor_cooldown;
+        if (bounces.any() && cooled_down) || bounces.exited {
+            match model.recolor.color_mode {
+                ColorMode::BrightnessPulse { .. } => logo.pulse(),
+                _ => logo.recolor(&model.images, &model.recolor),
+            }
+        }
+
+        if bounces.any() && logo.bounce_tracker.is_corner() {
+            log_message!("Corner hit after {}", format_duration(model.since_corner));
+            model.corner_hits += 1;
+            model.longest_without_corner = model.longest_without_corner.max(model.since_corner);
+            model.since_corner = 0.0;
+            if let Some(duration) = model.corner_freeze {
+                logo.freeze_timer = duration;
+            }
+            if model.corner_celebration {
+                model
+                    .confetti
+                    .extend(spawn_confetti(logo.rect.xy(), app.window_rect().h()));
+                model.corner_toast = CORNER_TOAST_DURATION;
+            }
+        }
+
+        if bounces.any() {
+            bounced = true;
+            model.bounce_count += 1;
+            if model.milestone_effects && model.bounce_count.is_multiple_of(BOUNCE_MILESTONE) {
+                model.milestone_flash = MILESTONE_FLASH_DURATION;
+            }
+        }
+    }
+
+    if let Some(image) = model
+        .image_cycler
+        .as_mut()
+        .and_then(|cycler| cycler.advance(bounced, delta_time))
+    {
+        model.replace_image(image, win);
+    }
+
+    if let Some(interval) = model.spawn_interval {
+        model.since_spawn += delta_time;
+        if model.since_spawn >= interval && model.logos.len() < model.max_logos {
+            model.since_spawn = 0.0;
+            let next = model.logos.len();
+            let logos = spawn_logos(
+                &model.image_config,
+                &model.images,
+                region_in(app.window_rect(), model.region),
+                model.image_scale,
+                next..next + 1,
+            );
+            model.logos.extend(logos);
+        }
+    }
+
+    if model.logo_collision {
+        for j in 1..model.logos.len() {
+            let (head, tail) = model.logos.split_at_mut(j);
+            let b = &mut tail[0];
+
+            for a in head.iter_mut() {
+                if let Some((a_rect, a_vel, b_rect, b_vel)) =
+                    collide_logos(a.rect, a.vel, b.rect, b.vel)
+                {
+                    a.rect = a_rect;
+                    a.vel = a_vel;
+                    b.rect = b_rect;
+                    b.vel = b_vel;
+                    a.recolor(&model.images, &model.recolor);
+                    b.recolor(&model.images, &model.recolor);
+                }
+            }
+        }
+    }
+}
+
+/// Resolves an overlap between two logos by pushing them apart along the axis
+/// of least penetration and exchanging their velocities on that axis, as for
+/// an elastic collision of equal masses. Returns `None` if they don't overlap.
+fn collide_logos(
+    a_rect: Rect,
+    a_vel: Vec2,
+    b_rect: Rect,
+    b_vel: Vec2,
+) -> Option<(Rect, Vec2, Rect, Vec2)> {
+    let overlap = a_rect.overlap(b_rect)?;
+    if overlap.w() <= 0.0 || overlap.h() <= 0.0 {
+        return None;
+    }
+
+    let (mut a_vel, mut b_vel) = (a_vel, b_vel);
+    let offset = b_rect.xy() - a_rect.xy();
+
+    let push = if overlap.w() < overlap.h() {
+        let sign = if offset.x >= 0.0 { 1.0 } else { -1.0 };
+        if (b_vel.x - a_vel.x) * sign < 0.0 {
+            std::mem::swap(&mut a_vel.x, &mut b_vel.x);
+        }
+        Vec2::new(sign * overlap.w() / 2.0, 0.0)
+    } else {
+        let sign = if offset.y >= 0.0 { 1.0 } else { -1.0 };
+        if (b_vel.y - a_vel.y) * sign < 0.0 {
+            std::mem::swap(&mut a_vel.y, &mut b_vel.y);
+        }
+        Vec2::new(0.0, sign * overlap.h() / 2.0)
+    };
+
+    Some((a_rect.shift(-push), a_vel, b_rect.shift(push), b_vel))
+}
+
+/// A slow Lissajous drift within `amount` pixels of the origin, so the scene
+/// never sits still long enough to burn in. The two axes use different periods
+/// so it doesn't just trace a line.
+fn pixel_shift_offset(time: f32, amount: f32, period: f32) -> Vec3 {
+    let phase = time / period * TAU;
+    Vec3::new(phase.sin(), (phase * 0.7).cos(), 0.0) * amount
+}
+
+fn view(app: &App, model: &Model, frame: Frame) {
+    if model.occluded || model.minimized {
+        return;
+    }
+
+    frame.clear(BLACK);
+
+    // `app.draw()` resets what has been drawn so far, so fetch it only once.
+    let base_draw = app.draw();
+
+    // Drawn in window points either way, scaled down to the internal size.
+    let internal_size = internal_size(frame.texture_size(), model.internal_height);
+    let base_draw = match internal_size {
+        Some([width, _]) => {
+            base_draw.background().color(BLACK);
+            base_draw.scale(width as f32 / app.window_rect().w())
+        }
+        None => base_draw,
+    };
+
+    // Everything shrinks towards the middle as it fades out.
+    let exit_progress = model
+        .exiting
+        .map_or(0.0, |exiting| (exiting / EXIT_DURATION).min(1.0));
+    let base_draw = if exit_progress > 0.0 {
+        base_draw.scale(1.0 - exit_progress)
+    } else {
+        base_draw
+    };
+
+    let base_draw = match model.pixel_shift {
+        Some((amount, period)) => base_draw.translate(pixel_shift_offset(app.time, amount, period)),
+        None => base_draw,
+    };
+
+    if let Some(background) = &model.background {
+        let win = app.window_rect();
+        let [width, height] = background.texture.size();
+        let size = Vec2::new(width as f32, height as f32);
+        let scale = background.fit.scale(size, win.wh());
+
+        base_draw
+            .texture(&background.texture)
+            .xy(win.xy())
+            .wh(size * scale);
+    }
+
+    if let Some(obstacle) = model.obstacle {
+        let obstacle = region_in(app.window_rect(), obstacle);
+        base_draw
+            .rect()
+            .xy(obstacle.xy())
+            .wh(obstacle.wh())
+            .color(rgb8(40, 40, 40))
+            .stroke(GRAY)
+            .stroke_weight(if model.is_preview { 1.0 } else { 2.0 });
+    }
+
+    if model.region_border {
+        let region = region_in(app.window_rect(), model.region);
+        base_draw
+            .rect()
+            .xy(region.xy())
+            .wh(region.wh())
+            .no_fill()
+            .stroke(GRAY)
+            .stroke_weight(if model.is_preview { 1.0 } else { 2.0 });
+    }
+
+    let draw = match model.scale_filter {
+        ScaleFilter::Smooth => base_draw,
+        ScaleFilter::Nearest => base_draw.sampler(
+            wgpu::SamplerBuilder::new()
+                .min_filter(wgpu::FilterMode::Nearest)
+                .mag_filter(wgpu::FilterMode::Nearest)
+                .into_descriptor(),
+        ),
+    };
+
+    if let Some((photo, texture)) = model
+        .photo_frame
+        .as_ref()
+        .and_then(|photo| Some((photo, photo.texture.as_ref()?)))
+    {
+        let win = app.window_rect();
+        let [width, height] = texture.size();
+        let size = Vec2::new(width as f32, height as f32);
+        let scale = BackgroundFit::Contain.scale(size, win.wh());
+
+        draw.texture(texture)
+            .area(photo.source_area())
+            .xy(win.xy())
+            .wh(size * scale);
+    }
+
+    // A photo frame shows its image in place of the logos.
+    let logos: &[Logo] = if model.photo_frame.is_some() {
+        &[]
+    } else {
+        &model.logos
+    };
+
+    // Logos and echoes are uploaded with premultiplied alpha.
+    let premultiplied = draw.color_blend(PREMULTIPLIED_BLEND);
+
+    for echo in logos.iter().flat_map(|logo| &logo.echoes) {
+        let texture = wgpu::Texture::from_image(app, &echo.faded);
+        premultiplied.texture(&texture).xy(echo.xy).wh(echo.wh);
+    }
+
+    // Each image's textures are uploaded once per frame and shared by its logos.
+    let shadows: Vec<Option<wgpu::Texture>> = model
+        .images
+        .iter()
+        .map(|image| {
+            let shadow = image.shadow.as_ref()?;
+            Some(wgpu::Texture::from_image(app, &shadow.image))
+        })
+        .collect();
+    for logo in logos {
+        let (Some(shadow), Some(texture)) =
+            (&model.images[logo.source].shadow, &shadows[logo.source])
+        else {
+            continue;
+        };
+        let (xy, wh) = logo.draw_area(model.flip_on_bounce, model.squash_intensity);
+        draw.texture(texture).xy(xy + shadow.offset).wh(wh);
+    }
+
+    let fixed_glows: Vec<Option<wgpu::Texture>> = model
+        .images
+        .iter()
+        .map(|image| {
+            let fixed = image.neon_glow.as_ref()?.fixed.as_ref()?;
+            Some(wgpu::Texture::from_image(app, fixed))
+        })
+        .collect();
+    for logo in logos {
+        let Some(neon_glow) = &model.images[logo.source].neon_glow else {
+            continue;
+        };
+        let texture = match (&fixed_glows[logo.source], &logo.neon_image) {
+            (Some(texture), _) => texture.clone(),
+            (None, Some(image)) => wgpu::Texture::from_image(app, image),
+            (None, None) => continue,
+        };
+        let (xy, wh) = logo.draw_area(model.flip_on_bounce, model.squash_intensity);
+        draw.texture(&texture)
+            .xy(xy)
+            .wh(wh + wh.signum() * neon_glow.padding * 2.0);
+    }
+
+    let outlines: Vec<Option<wgpu::Texture>> = model
+        .images
+        .iter()
+        .map(|image| {
+            let outline = image.outline.as_ref()?;
+            Some(wgpu::Texture::from_image(app, &outline.image))
+        })
+        .collect();
+    for logo in logos {
+        let (Some(outline), Some(texture)) =
+            (&model.images[logo.source].outline, &outlines[logo.source])
+        else {
+            continue;
+        };
+        let (xy, wh) = logo.draw_area(model.flip_on_bounce, model.squash_intensity);
+        for i in 0..8 {
+            let angle = i as f32 * TAU / 8.0;
+            let offset = Vec2::new(angle.cos(), angle.sin()) * outline.thickness;
+            draw.texture(texture).xy(xy + offset).wh(wh);
+        }
+    }
+
+    if let Some(intensity) = model.glow_intensity {
+        for logo in logos.iter().filter(|logo| logo.glow_timer > 0.0) {
+            let remaining = logo.glow_timer / GLOW_DURATION;
+            let scale = 1.0 + (1.0 - remaining) * 0.6;
+            let alpha = (intensity * remaining * 255.0) as u8;
+
+            draw.ellipse()
+                .xy(logo.rect.xy())
+                .wh(logo.rect.wh() * scale)
+                .color(srgba8(
+                    logo.color.red,
+                    logo.color.green,
+                    logo.color.blue,
+                    alpha,
+                ));
+        }
+    }
+
+    // A pulsing halo around logos frozen after a corner hit.
+    for logo in logos.iter().filter(|logo| logo.freeze_timer > 0.0) {
+        let pulse = 0.5 + 0.5 * (app.time * 12.0).sin();
+        draw.ellipse()
+            .xy(logo.rect.xy())
+            .wh(logo.rect.wh() * (1.3 + 0.2 * pulse))
+            .color(srgba8(
+                logo.color.red,
+                logo.color.green,
+                logo.color.blue,
+                (80.0 + 100.0 * pulse) as u8,
+            ));
+    }
+
+    for logo in logos {
+        let texture = wgpu::Texture::from_image(app, logo.drawn_image());
+
+        let (xy, wh) = logo.draw_area(model.flip_on_bounce, model.squash_intensity);
+        premultiplied.texture(&texture).xy(xy).wh(wh);
+    }
+
+    let confetti_size = (app.window_rect().h() * 0.012).max(2.0);
+    for confetti in &model.confetti {
+        let [r, g, b] = confetti.color;
+        draw.rect()
+            .xy(confetti.xy)
+            .w_h(confetti_size, confetti_size * 0.6)
+            .rotate(confetti.life * 8.0)
+            .color(rgba(r, g, b, (confetti.life / CONFETTI_LIFETIME).min(1.0)));
+    }
+
+    if model.corner_toast > 0.0 {
+        let win = app.window_rect();
+        draw.text("Hit the corner!")
+            .xy(win.pad(16.0).xy())
+            .wh(win.pad(16.0).wh())
+            .align_text_top()
+            .font_size(if model.is_preview { 12 } else { 36 })
+            .color(rgba(1.0, 1.0, 1.0, model.corner_toast.min(1.0)));
+    }
+
+    if model.milestone_flash > 0.0 {
+        let alpha = model.milestone_flash / MILESTONE_FLASH_DURATION * 0.5;
+        draw.rect()
+            .xy(app.window_rect().xy())
+            .wh(app.window_rect().wh())
+            .color(rgba(1.0, 1.0, 1.0, alpha));
+    }
+
+    if model.show_bounce_counter {
+        let win = app.window_rect();
+        draw.text(&format!("Bounces: {}", model.bounce_count))
+            .xy(win.pad(16.0).xy())
+            .wh(win.pad(16.0).wh())
+            .left_justify()
+            .align_text_bottom()
+            .font_size(if model.is_preview { 10 } else { 24 })
+            .color(WHITE);
+    }
+
+    if let Some((text, [r, g, b], position)) = &model.banner {
+        let win = app.window_rect();
+        let font_size = if model.is_preview { 8 } else { 24 };
+        let height = font_size as f32 * 2.0;
+        let y = match position {
+            BannerPosition::Top => win.top() - height / 2.0,
+            BannerPosition::Bottom => win.bottom() + height / 2.0,
+        };
+        draw.rect()
+            .x_y(win.x(), y)
+            .w_h(win.w(), height)
+            .color(srgba8(*r, *g, *b, 220));
+        draw.text(text)
+            .x_y(win.x(), y)
+            .w_h(win.w() - height, height)
+            .font_size(font_size)
+            .color(WHITE);
+    }
+
+    let fade_in = if model.startup_fade > 0.0 {
+        (app.time / model.startup_fade).min(1.0)
+    } else {
+        1.0
+    };
+    let brightness = model.dim * fade_in * (1.0 - exit_progress);
+    if brightness < 1.0 {
+        draw.rect()
+            .xy(app.window_rect().xy())
+            .wh(app.window_rect().wh())
+            .color(rgba(0.0, 0.0, 0.0, 1.0 - brightness));
+    }
+
+    let Some(size) = internal_size else {
+        // The frame is skipped; the next one gets another try.
+        if let Err(error) = draw.to_frame(app, &frame) {
+            log_message!("Unable to draw a frame: {:?}", error);
+        }
+        return;
+    };
+
+    let window = app.main_window();
+    let device = window.device();
+    let mut offscreen = model.offscreen.borrow_mut();
+    if offscreen
+        .as_ref()
+        .is_none_or(|offscreen| offscreen.texture.size() != size)
+    {
+        *offscreen = Some(Offscreen::new(device, size, frame.texture_msaa_samples()));
+    }
+    let offscreen = offscreen.as_mut().unwrap();
+
+    let mut encoder = frame.command_encoder();
+    offscreen
+        .renderer
+        .render_to_texture(device, &mut encoder, &draw, &offscreen.texture);
+    offscreen
+        .reshaper
+        .encode_render_pass(frame.texture_view(), &mut encoder);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WALLS: [WallMode; 4] = [WallMode::Reflect; 4];
+
+    fn window() -> Rect {
+        Rect::from_x_y_w_h(0.0, 0.0, 800.0, 600.0)
+    }
+
+    #[test]
+    fn step_moves_freely_inside_window() {
+        let rect = Rect::from_x_y_w_h(0.0, 0.0, 100.0, 50.0);
+        let (rect, vel, flags) = step(rect, Vec2::new(10.0, -20.0), window(), 1.0, &WALLS, None);
+
+        assert_eq!(rect.xy(), Vec2::new(10.0, -20.0));
+        assert_eq!(vel, Vec2::new(10.0, -20.0));
+        assert!(!flags.any());
+    }
+
+    #[test]
+    fn step_reflects_off_left_wall() {
+        let rect = Rect::from_x_y_w_h(-340.0, 0.0, 100.0, 50.0);
+        let (rect, vel, flags) = step(rect, Vec2::new(-50.0, 0.0), window(), 1.0, &WALLS, None);
+
+        assert_eq!(rect.left(), window().left());
+        assert_eq!(vel.x, 50.0);
+        assert_eq!(
+            flags,
+            BounceFlags {
+                left: true,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn step_reflects_off_top_right_corner() {
+        let rect = Rect::from_x_y_w_h(340.0, 265.0, 100.0, 50.0);
+        let (rect, vel, flags) = step(rect, Vec2::new(50.0, 50.0), window(), 1.0, &WALLS, None);
+
+        assert_eq!(rect.right(), window().right());
+        assert_eq!(rect.top(), window().top());
+        assert_eq!(vel, Vec2::new(-50.0, -50.0));
+        assert!(flags.right && flags.top);
+        assert!(!flags.left && !flags.bottom);
+    }
+
+    #[test]
+    fn step_reflects_fast_logo_over_large_dt() {
+        let win = window();
+        let rect = Rect::from_x_y_w_h(300.0, 0.0, 100.0, 50.0);
+        let (rect, vel, flags) = step(rect, Vec2::new(100_000.0, 0.0), win, 2.0, &WALLS, None);
+
+        assert_eq!(rect.right(), win.right());
+        assert!(vel.x < 0.0 && vel.length() <= MAX_LOGO_SPEED);
+        assert!(flags.right);
+
+        let (rect, _, _) = step(rect, vel, win, 1.0 / 60.0, &WALLS, None);
+        assert!(rect.left() >= win.left() && rect.right() <= win.right());
+    }
+
+    #[test]
+    fn pinned_logo_is_freed() {
+        let win = window();
+        let mut rect = Rect::from_x_y_w_h(-350.0, 0.0, 100.0, 50.0);
+        let mut vel = Vec2::new(0.0, 40.0);
+        let mut tracker = BounceTracker::default();
+        let dt = 1.0 / 60.0;
+
+        let mut bounces = Vec::new();
+        for _ in 0..30 {
+            let (next_rect, next_vel, flags) = step(rect, vel, win, dt, &WALLS, None);
+            (rect, vel) = tracker.unstick(next_rect, next_vel, win, flags, dt);
+            bounces.push(flags.left);
+        }
+
+        assert!(vel.x > 0.0);
+        assert!(rect.left() > win.left());
+        assert!(bounces[bounces.len() - 10..].iter().all(|&left| !left));
+    }
+
+    #[test]
+    fn normalized_speed_moves_same_distance_per_second_at_any_refresh_rate() {
+        let start = Rect::from_x_y_w_h(0.0, 0.0, 100.0, 50.0);
+        let vel = Vec2::new(90.0, -45.0);
+        let after_one_second = |hertz: u32| {
+            let interval = refresh_update_interval([None, Some(hertz * 1000)]).unwrap();
+            let mut rect = start;
+            for _ in 0..hertz {
+                rect = step(rect, vel, window(), frame_delta(interval), &WALLS, None).0;
+            }
+            rect.xy()
+        };
+
+        for hertz in [30, 60, 144, 240] {
+            assert!(after_one_second(hertz).distance(vel) < 1e-2);
+        }
+        assert_eq!(refresh_update_interval([None, Some(0)]), None);
+    }
+
+    #[test]
+    fn step_keeps_logo_inside_window() {
+        let win = window();
+        let mut rect = Rect::from_x_y_w_h(0.0, 0.0, 100.0, 50.0);
+        let mut vel = Vec2::new(370.0, -290.0);
+
+        for _ in 0..1000 {
+            let (next_rect, next_vel, _) = step(rect, vel, win, 1.0 / 60.0, &WALLS, None);
+            rect = next_rect;
+            vel = next_vel;
+
+            assert!(rect.left() >= win.left() && rect.right() <= win.right());
+            assert!(rect.bottom() >= win.bottom() && rect.top() <= win.top());
+        }
+    }
+
+    #[test]
+    fn step_reflects_at_wall_margin() {
+        let margin = 20.0;
+        let bounds = bounds_with_margin(window(), margin);
+        let rect = Rect::from_x_y_w_h(-320.0, 0.0, 100.0, 50.0);
+        let (rect, vel, flags) = step(rect, Vec2::new(-50.0, 0.0), bounds, 1.0, &WALLS, None);
+
+        assert_eq!(rect.left(), window().left() + margin);
+        assert_eq!(vel.x, 50.0);
+        assert!(flags.left);
+    }
+
+    fn obstacle() -> Option<Rect> {
+        Some(Rect::from_x_y_w_h(0.0, 0.0, 100.0, 100.0))
+    }
+
+    #[test]
+    fn step_reflects_off_obstacle_side() {
+        let rect = Rect::from_x_y_w_h(-120.0, 0.0, 100.0, 50.0);
+        let (rect, vel, flags) = step(
+            rect,
+            Vec2::new(50.0, 0.0),
+            window(),
+            1.0,
+            &WALLS,
+            obstacle(),
+        );
+
+        assert_eq!(rect.right(), -50.0);
+        assert_eq!(vel, Vec2::new(-50.0, 0.0));
+        assert!(flags.obstacle);
+    }
+
+    #[test]
+    fn step_reflects_off_obstacle_corner_on_both_axes() {
+        let rect = Rect::from_x_y_w_h(-75.0, 75.0, 20.0, 20.0);
+        let (rect, vel, flags) = step(
+            rect,
+            Vec2::new(20.0, -20.0),
+            window(),
+            1.0,
+            &WALLS,
+            obstacle(),
+        );
+
+        assert_eq!(rect.right(), -50.0);
+        assert_eq!(rect.bottom(), 50.0);
+        assert_eq!(vel, Vec2::new(-20.0, 20.0));
+        assert!(flags.obstacle);
+    }
+
+    #[test]
+    fn step_catches_fast_logo_passing_through_obstacle() {
+        let rect = Rect::from_x_y_w_h(-200.0, 0.0, 20.0, 20.0);
+        let (rect, vel, flags) = step(
+            rect,
+            Vec2::new(400.0, 0.0),
+            window(),
+            1.0,
+            &WALLS,
+            obstacle(),
+        );
+
+        assert_eq!(rect.right(), -50.0);
+        assert_eq!(vel.x, -400.0);
+        assert!(flags.obstacle);
+    }
+
+    #[test]
+    fn step_pushes_logo_out_of_obstacle() {
+        let rect = Rect::from_x_y_w_h(40.0, 0.0, 20.0, 20.0);
+        let (rect, _, flags) = step(rect, Vec2::ZERO, window(), 1.0, &WALLS, obstacle());
+
+        assert_eq!(rect.left(), 50.0);
+        assert!(flags.obstacle);
+    }
+
+    #[test]
+    fn photo_frame_pans_within_the_image() {
+        let mut photo = PhotoFrame {
+            pan_secs: 10.0,
+            zoom: (1.0, 2.0),
+            recolor: false,
+            elapsed: 0.0,
+            from: PhotoShot {
+                zoom: 1.0,
+                center: Vec2::new(-1.0, 1.0),
+            },
+            to: PhotoShot {
+                zoom: 2.0,
+                center: Vec2::new(1.0, -1.0),
+            },
+            texture: None,
+        };
+        assert_eq!(photo.source_area(), Rect::from_x_y_w_h(0.5, 0.5, 1.0, 1.0));
+
+        for _ in 0..9 {
+            assert!(!photo.advance(1.0));
+            let area = photo.source_area();
+            assert!(area.left() >= 0.0 && area.right() <= 1.0);
+            assert!(area.bottom() >= 0.0 && area.top() <= 1.0);
+        }
+        assert!(photo.advance(1.0));
+        assert_eq!(
+            photo.source_area(),
+            Rect::from_x_y_w_h(1.0 - 0.25, 0.25, 0.5, 0.5)
+        );
+    }
+
+    #[test]
+    fn gravity_curves_path_but_keeps_speed() {
+        let vel = Vec2::new(100.0, 0.0);
+        let well = Vec2::new(0.0, -200.0);
+        let pulled = gravity_pull(Vec2::ZERO, vel, well, 0.5, 600.0, 0.1);
+
+        assert!(pulled.y < 0.0);
+        assert!((pulled.length() - vel.length()).abs() < 1e-3);
+
+        // Right at the well there's no direction to pull in.
+        assert_eq!(gravity_pull(well, vel, well, 0.5, 600.0, 0.1), vel);
+    }
+
+    #[test]
+    fn same_seed_gives_same_hue_sequence() {
+        let hues = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut hue = 0;
+            (0..20)
+                .map(|_| {
+                    hue = next_hue(&mut rng, hue);
+                    hue
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(hues(42), hues(42));
+        assert_ne!(hues(42), hues(43));
+    }
+
+    #[test]
+    fn config_path_honors_env_override() {
+        let path = env::temp_dir()
+            .join("dvd-screensaver-test")
+            .join("custom.ini");
+
+        assert_eq!(resolve_config_path(None, Some(path.clone().into())), path);
+    }
+
+    #[test]
+    fn config_file_flag_is_used_verbatim() {
+        let path = env::temp_dir()
+            .join("dvd-screensaver-test")
+            .join("flag.ini");
+        let env_path = env::temp_dir().join("dvd-screensaver-test").join("env.ini");
+
+        assert_eq!(
+            resolve_config_path(Some(&path), Some(env_path.into())),
+            path
+        );
+    }
+
+    #[test]
+    fn option_without_value_is_an_error() {
+        let mut args: Vec<String> = ["dvd", "/s", "--set", "speed=80"]
+            .map(String::from)
+            .to_vec();
+        assert_eq!(
+            take_option_value(&mut args, "--set"),
+            Ok(Some("speed=80".to_string()))
+        );
+        assert_eq!(take_option_value(&mut args, "--set"), Ok(None));
+        assert_eq!(args, ["dvd", "/s"]);
+
+        args.push("--set".to_string());
+        assert!(take_option_value(&mut args, "--set").is_err());
+    }
+
+    #[test]
+    fn icon_set_survives_config_round_trip() {
+        let config = ScreenSaverConfig {
+            icon_set: vec![
+                IconSource::Custom(PathBuf::from("/pictures/a:b.png")),
+                IconSource::BuiltIn(1),
+            ],
+            ..Default::default()
+        };
+
+        let mut written = Vec::new();
+        write_config(&mut written, &config).unwrap();
+        let parsed = parse_config(std::str::from_utf8(&written).unwrap());
+
+        assert_eq!(parsed.icon_set, config.icon_set);
+    }
+
+    #[test]
+    fn icon_set_count_past_end_of_config_stops_at_last_line() {
+        let config = ScreenSaverConfig {
+            icon_set: vec![IconSource::BuiltIn(1)],
+            ..Default::default()
+        };
+        let mut written = Vec::new();
+        write_config(&mut written, &config).unwrap();
+        let written = String::from_utf8(written).unwrap();
+
+        // Cut off right after the icon set, with a huge count before it.
+        let mut lines: Vec<&str> = written.lines().collect();
+        let entry = lines.iter().position(|line| *line == "builtin:1").unwrap();
+        lines.truncate(entry + 1);
+        lines[entry - 1] = "18446744073709551615";
+        let parsed = parse_config(&lines.join("\n"));
+
+        assert_eq!(parsed.icon_set, config.icon_set);
+    }
+
+    #[test]
+    fn image_cache_evicts_least_recently_used() {
+        // Each image is 4 KiB, and the cache fits two.
+        let image = DynamicImage::new_rgba8(32, 32);
+        let mut cache = ImageCache {
+            max_bytes: 2 * 4096,
+            ..Default::default()
+        };
+        cache.insert(1, &image);
+        cache.insert(2, &image);
+        assert!(cache.get(1).is_some());
+        cache.insert(3, &image);
+
+        assert!(cache.get(2).is_none());
+        assert!(cache.get(1).is_some());
+        assert!(cache.get(3).is_some());
+        assert_eq!(cache.bytes, 2 * 4096);
+    }
+
+    /// An ICO file holding a blank PNG of each size.
+    fn icon_file(sizes: &[u8]) -> Vec<u8> {
+        let pngs: Vec<Vec<u8>> = sizes
+            .iter()
+            .map(|&size| {
+                let mut png = Vec::new();
+                DynamicImage::new_rgba8(size as u32, size as u32)
+                    .write_to(&mut png, image::ImageOutputFormat::Png)
+                    .unwrap();
+                png
+            })
+            .collect();
+
+        let mut icon = vec![0, 0, 1, 0, sizes.len() as u8, 0];
+        let mut offset = 6 + 16 * pngs.len() as u32;
+        for (png, &size) in pngs.iter().zip(sizes) {
+            icon.extend_from_slice(&[size, size, 0, 0, 1, 0, 32, 0]);
+            icon.extend_from_slice(&(png.len() as u32).to_le_bytes());
+            icon.extend_from_slice(&offset.to_le_bytes());
+            offset += png.len() as u32;
+        }
+        for png in &pngs {
+            icon.extend_from_slice(png);
+        }
+        icon
+    }
+
+    /// An animated cursor whose only frame is `icon`.
+    fn ani_file(icon: &[u8]) -> Vec<u8> {
+        let mut frames = b"fram".to_vec();
+        frames.extend_from_slice(b"icon");
+        frames.extend_from_slice(&(icon.len() as u32).to_le_bytes());
+        frames.extend_from_slice(icon);
+
+        let mut ani = b"RIFF\0\0\0\0ACONLIST".to_vec();
+        ani.extend_from_slice(&(frames.len() as u32).to_le_bytes());
+        ani.extend_from_slice(&frames);
+        ani
+    }
+
+    #[test]
+    fn cursor_data_uris_decode_like_files() {
+        let icon = icon_file(&[16]);
+        for (media_type, bytes) in [
+            ("image/x-icon", icon.clone()),
+            ("image/x-win-cursor", icon.clone()),
+            ("application/x-navi-animation", ani_file(&icon)),
+        ] {
+            let uri = format!(
+                "data:{};base64,{}",
+                media_type,
+                base64::engine::general_purpose::STANDARD.encode(&bytes)
+            );
+            assert_eq!(load_image_safe(&uri, (16, 16)).unwrap().width(), 16);
+        }
+    }
+
+    #[test]
+    fn icon_loads_from_data_uri() {
+        let mut png = Vec::new();
+        DynamicImage::new_rgba8(3, 2)
+            .write_to(&mut png, image::ImageOutputFormat::Png)
+            .unwrap();
+        let uri = format!(
+            "data:image/png;base64,{}",
+            base64::engine::general_purpose::STANDARD.encode(&png)
+        );
+
+        let image = load_image_safe(&uri, (64, 64)).unwrap();
+        assert_eq!(image.dimensions(), (3, 2));
+
+        assert!(load_image_safe("data:image/png;base64,!!!", (64, 64)).is_err());
+        assert!(load_image_safe("data:image/png,abc", (64, 64)).is_err());
+    }
+
+    #[test]
+    fn stats_json_config_keeps_value_types() {
+        assert_eq!(json_config_value("speed", "50"), "50");
+        assert_eq!(json_config_value("size_factor", "0.16"), "0.16");
+        assert_eq!(json_config_value("shadow", "true"), "true");
+        assert_eq!(json_config_value("seed", ""), "null");
+        assert_eq!(
+            json_config_value("color_mode", "rainbow:60"),
+            "\"rainbow:60\""
+        );
+        assert_eq!(json_config_value("banner_color", "141414"), "\"141414\"");
+        assert_eq!(json_config_value("banner_text", "2024"), "\"2024\"");
+    }
+
+    #[test]
+    fn export_embeds_icon_set_files() {
+        let dir = env::temp_dir().join("dvd-screensaver-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let png_path = dir.join("icon-set.png");
+        DynamicImage::new_rgba8(3, 2).save(&png_path).unwrap();
+        let icon = icon_file(&[16]);
+        let cursor_path = dir.join("icon-set.cur");
+        std::fs::write(&cursor_path, &icon).unwrap();
+        let ani_path = dir.join("icon-set.ani");
+        std::fs::write(&ani_path, ani_file(&icon)).unwrap();
+        // Only the media type is checked, as decoding these needs features.
+        let extra_paths = ["jxl", "avif"].map(|extension| {
+            let path = dir.join(format!("icon-set.{}", extension));
+            std::fs::write(&path, b"not decoded").unwrap();
+            path
+        });
+
+        let mut icon_set = vec![
+            IconSource::BuiltIn(1),
+            IconSource::Custom(png_path),
+            IconSource::Custom(cursor_path),
+            IconSource::Custom(ani_path),
+        ];
+        icon_set.extend(extra_paths.iter().cloned().map(IconSource::Custom));
+        let config = ScreenSaverConfig {
+            icon_set,
+            ..Default::default()
+        };
+
+        let export_path = dir.join("icon-set.dvdsaver");
+        export_settings(&config, &export_path).unwrap();
+        let imported = import_settings(&export_path).unwrap();
+
+        assert_eq!(imported.icon_set[0], IconSource::BuiltIn(1));
+        let uris: Vec<String> = imported.icon_set[1..]
+            .iter()
+            .map(|icon| match icon {
+                IconSource::Custom(uri) => uri.to_string_lossy().to_string(),
+                IconSource::BuiltIn(_) => panic!("expected a custom icon"),
+            })
+            .collect();
+        let load = |uri: &str| load_image_safe(uri, (16, 16)).unwrap().dimensions();
+        assert_eq!(load(&uris[0]), (3, 2));
+        assert_eq!(load(&uris[1]), (16, 16));
+        assert_eq!(load(&uris[2]), (16, 16));
+        for (uri, extension) in uris[3..].iter().zip(["jxl", "avif"]) {
+            let (media_type, _) = decode_data_uri(uri).unwrap();
+            assert_eq!(media_type_extension(media_type), Some(extension));
+        }
+    }
+
+    #[test]
+    fn icon_picks_closest_size_not_smaller_than_target() {
+        let icon = icon_file(&[16, 64]);
+
+        assert_eq!(decode_icon(&icon, (8, 8)).unwrap().width(), 16);
+        assert_eq!(decode_icon(&icon, (20, 10)).unwrap().width(), 64);
+        assert_eq!(decode_icon(&icon, (300, 300)).unwrap().width(), 64);
+    }
 }